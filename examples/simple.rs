@@ -3,80 +3,72 @@ use box_drawing_table::*;
 fn main() {
     let mut table = Table::new(vec![
         Border::Double.into(),
-        Column::Cells {
-            width: CellSize::Flexible,
-        },
+        Column::flexible_width(),
         Border::Double.into(),
-        Column::Cells {
-            width: CellSize::Flexible,
-        },
+        Column::flexible_width(),
         Border::Single.into(),
-        Column::Cells {
-            width: CellSize::Fixed(10),
-        },
+        Column::fixed_width(10),
         Border::Double.into(),
     ]);
     table.append_row(Border::Double.into());
-    table.append_row(Row::Cells {
-        height: CellSize::Flexible,
-        cells: vec![Cell::left(""), Cell::left("w=*"), Cell::left("w=10")],
-    });
+    table.append_row(Row::flexible_height(vec![
+        Cell::left(""),
+        Cell::left("w=*"),
+        Cell::left("w=10"),
+    ]));
     table.append_row(Border::Single.into());
-    table.append_row(Row::Cells {
-        height: CellSize::Flexible,
-        cells: vec![
-            Cell::left("h=*"),
-            Cell {
-                value: "123456789012345".into(),
-                align: Align::Left,
-                style: ansi_term::Style::new(),
-            },
-            Cell {
-                value: "123456789012345".into(),
-                align: Align::Left,
-                style: ansi_term::Style::new(),
-            },
-        ],
-    });
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("h=*"),
+        Cell {
+            value: "123456789012345".into(),
+            align: Some(Align::Left),
+            style: Style::new(),
+            hyperlink: None,
+        },
+        Cell {
+            value: "123456789012345".into(),
+            align: Some(Align::Left),
+            style: Style::new(),
+            hyperlink: None,
+        },
+    ]));
     table.append_row(Border::Double.into());
-    table.append_row(Row::Cells {
-        height: CellSize::Fixed(2),
-        cells: vec![
+    table.append_row(Row::fixed_height(
+        2,
+        vec![
             Cell::left("h=2"),
             Cell {
                 value: "Left".into(),
-                align: Align::Left,
-                style: ansi_term::Style::new()
-                    .bold()
-                    .fg(ansi_term::Color::RGB(245, 66, 170)),
+                align: Some(Align::Left),
+                style: Style::new().bold().fg(Color::RGB(245, 66, 170)),
+                hyperlink: None,
             },
             Cell {
                 value: "Right".into(),
-                align: Align::Right,
-                style: ansi_term::Style::new()
-                    .underline()
-                    .on(ansi_term::Color::RGB(66, 206, 245)),
+                align: Some(Align::Right),
+                style: Style::new().underline().on(Color::RGB(66, 206, 245)),
+                hyperlink: None,
             },
         ],
-    });
+    ));
     table.append_row(Border::Single.into());
-    table.append_row(Row::Cells {
-        height: CellSize::Flexible,
-        cells: vec![
-            Cell::left("h=*"),
-            Cell {
-                value: "{padl:2, padr:1}".into(),
-                align: Align::CenterPadded { padl: 2, padr: 1 },
-                style: ansi_term::Style::new().strikethrough(),
-            },
-            Cell {
-                value: "{padr:1}あいうえお1234567890かきくけこ".into(),
-                align: Align::RightPadded { padr: 1 },
-                style: ansi_term::Style::new().fg(ansi_term::Color::RGB(221, 245, 66)),
-            },
-        ],
-    });
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("h=*"),
+        Cell {
+            value: "{padl:2, padr:1}".into(),
+            align: Some(Align::CenterPadded { padl: 2, padr: 1 }),
+            style: Style::new().strikethrough(),
+            hyperlink: None,
+        },
+        Cell {
+            value: "{padr:1}あいうえお1234567890かきくけこ".into(),
+            align: Some(Align::RightPadded { padr: 1 }),
+            style: Style::new().fg(Color::RGB(221, 245, 66)),
+            hyperlink: None,
+        },
+    ]));
     table.append_row(Border::Double.into());
 
+    table.set_color_depth(ColorDepth::detect());
     print!("{}", table);
 }
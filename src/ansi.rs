@@ -0,0 +1,406 @@
+//! Handling of strings containing ANSI escape sequences.
+
+use crate::Style;
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+
+/// Closes an OSC 8 hyperlink.
+const LINK_END: &str = "\x1b]8;;\x1b\\";
+
+/// A piece of a string, either visible text or an escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Escape(&'a str),
+}
+
+/// Splits `s` into text and escape sequences (CSI, OSC and two-character escapes).
+pub(crate) fn tokenize(s: &str) -> Vec<Token<'_>> {
+    let bytes = s.as_bytes();
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != ESC as u8 {
+            i += 1;
+            continue;
+        }
+        if text_start < i {
+            tokens.push(Token::Text(&s[text_start..i]));
+        }
+        let end = match bytes.get(i + 1) {
+            // CSI: parameter and intermediate bytes followed by a final byte
+            Some(b'[') => {
+                let mut j = i + 2;
+                while j < bytes.len() && (0x20..=0x3f).contains(&bytes[j]) {
+                    j += 1;
+                }
+                if j < bytes.len() && (0x40..=0x7e).contains(&bytes[j]) {
+                    j += 1;
+                }
+                j
+            }
+            // OSC: terminated by BEL or ST
+            Some(b']') => {
+                let mut j = i + 2;
+                loop {
+                    match bytes.get(j) {
+                        None => break j,
+                        Some(0x07) => break j + 1,
+                        Some(0x1b) if bytes.get(j + 1) == Some(&b'\\') => break j + 2,
+                        Some(_) => j += 1,
+                    }
+                }
+            }
+            Some(_) => i + 1 + s[i + 1..].chars().next().map_or(0, char::len_utf8),
+            None => i + 1,
+        };
+        tokens.push(Token::Escape(&s[i..end]));
+        i = end;
+        text_start = end;
+    }
+    if text_start < bytes.len() {
+        tokens.push(Token::Text(&s[text_start..]));
+    }
+    tokens
+}
+
+/// Returns the display width of `s`, ignoring escape sequences.
+pub(crate) fn width(s: &str) -> usize {
+    use unicode_width::UnicodeWidthStr;
+    if !s.contains(ESC) {
+        return s.width();
+    }
+    tokenize(s)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => text.width(),
+            Token::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// Removes every escape sequence from `s`.
+pub(crate) fn strip(s: &str) -> Cow<'_, str> {
+    if !s.contains(ESC) {
+        return Cow::Borrowed(s);
+    }
+    tokenize(s)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            Token::Escape(_) => None,
+        })
+        .collect::<String>()
+        .into()
+}
+
+/// Truncates `s` to at most `width` columns, keeping every escape sequence intact.
+pub(crate) fn truncate(s: &str, width: usize) -> String {
+    use unicode_width::UnicodeWidthChar;
+    let mut buf = String::new();
+    let mut total = 0;
+    for token in tokenize(s) {
+        match token {
+            Token::Text(text) => {
+                for c in text.chars() {
+                    let w = c.width().unwrap_or(0);
+                    if total + w > width {
+                        // drop the rest of the text, but not the escapes following it
+                        total = width;
+                        break;
+                    }
+                    total += w;
+                    buf.push(c);
+                }
+            }
+            Token::Escape(e) => buf.push_str(e),
+        }
+    }
+    buf
+}
+
+/// Makes `s` a hyperlink to `uri`.
+pub(crate) fn link(uri: &str, s: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}{}", uri, s, LINK_END)
+}
+
+/// Replaces the visible characters of `s` from the column `at` with `text`, keeping escape
+/// sequences. Both are assumed to be made of characters one column wide, such as box-drawing
+/// characters; the part of `text` going past the end of `s` is dropped.
+pub(crate) fn overwrite(s: &str, at: usize, text: &str) -> String {
+    let mut replacement = text.chars();
+    let mut buf = String::new();
+    let mut col = 0;
+    for token in tokenize(s) {
+        match token {
+            Token::Text(t) => {
+                for c in t.chars() {
+                    let r = if col >= at { replacement.next() } else { None };
+                    buf.push(r.unwrap_or(c));
+                    col += 1;
+                }
+            }
+            Token::Escape(e) => buf.push_str(e),
+        }
+    }
+    buf
+}
+
+/// Returns the parameters of `e` if it is an SGR sequence.
+fn sgr_params(e: &str) -> Option<&str> {
+    e.strip_prefix("\x1b[")?.strip_suffix('m')
+}
+
+fn is_reset(e: &str) -> bool {
+    sgr_params(e).is_some_and(|params| {
+        params
+            .split(';')
+            .all(|p| p.trim_start_matches('0').is_empty())
+    })
+}
+
+/// Returns the URI of `e` if it is an OSC 8 hyperlink sequence.
+fn link_uri(e: &str) -> Option<&str> {
+    let payload = e.strip_prefix("\x1b]8;")?;
+    let payload = payload
+        .strip_suffix('\x07')
+        .or_else(|| payload.strip_suffix("\x1b\\"))?;
+    payload.split_once(';').map(|(_, uri)| uri)
+}
+
+/// The graphic rendition and hyperlink in effect at some point of a string.
+#[derive(Debug, Default)]
+struct State<'a> {
+    sgr: Vec<&'a str>,
+    link: Option<&'a str>,
+}
+
+impl<'a> State<'a> {
+    fn update(&mut self, e: &'a str) {
+        if let Some(params) = sgr_params(e) {
+            if is_reset(e) {
+                self.sgr.clear();
+            } else {
+                if params.starts_with(';') || params.starts_with("0;") {
+                    self.sgr.clear();
+                }
+                self.sgr.push(e);
+            }
+        } else if let Some(uri) = link_uri(e) {
+            self.link = Some(e).filter(|_| !uri.is_empty());
+        }
+    }
+
+    /// Escape sequences restoring this state at the beginning of a line.
+    fn reopen(&self) -> String {
+        let mut buf = String::new();
+        buf.extend(self.link);
+        buf.extend(self.sgr.iter().copied());
+        buf
+    }
+
+    /// Escape sequences closing this state at the end of a line.
+    fn close(&self) -> String {
+        let mut buf = String::new();
+        if self.link.is_some() {
+            buf.push_str(LINK_END);
+        }
+        if !self.sgr.is_empty() {
+            buf.push_str("\x1b[0m");
+        }
+        buf
+    }
+}
+
+/// Wraps `s` into lines of at most `width` columns.
+///
+/// Escape sequences are kept where they are. Styles and hyperlinks still in effect at the end
+/// of a line are closed there and re-opened at the beginning of the next line, so that every
+/// line can be printed on its own.
+pub(crate) fn wrap(s: &str, width: usize) -> Vec<Cow<'_, str>> {
+    let wrap_opts = textwrap::Options::with_splitter(width, textwrap::NoHyphenation);
+    if !s.contains(ESC) {
+        return textwrap::wrap(s, wrap_opts);
+    }
+
+    // wrap the visible text, then put the escape sequences back
+    let mut plain = String::new();
+    let mut escapes = Vec::new();
+    for token in tokenize(s) {
+        match token {
+            Token::Text(text) => plain.push_str(text),
+            Token::Escape(e) => escapes.push((plain.len(), e)),
+        }
+    }
+
+    let wrapped = textwrap::wrap(&plain, wrap_opts);
+    let last = wrapped.len().saturating_sub(1);
+    let mut escapes = escapes.into_iter().peekable();
+    let mut state = State::default();
+    let mut pos = 0;
+    let mut lines = Vec::new();
+    for (i, line) in wrapped.iter().enumerate() {
+        let start = plain[pos..].find(line.as_ref()).map_or(pos, |o| pos + o);
+        let end = start + line.len();
+
+        // escapes in the whitespace between lines only change the state
+        while let Some((_, e)) = escapes.next_if(|(o, _)| *o < start) {
+            state.update(e);
+        }
+
+        let mut buf = state.reopen();
+        let mut cur = start;
+        while let Some((o, e)) = escapes.next_if(|(o, _)| *o <= end || i == last) {
+            let o = o.min(end);
+            buf.push_str(&plain[cur..o]);
+            buf.push_str(e);
+            state.update(e);
+            cur = o;
+        }
+        buf.push_str(&plain[cur..end]);
+        buf.push_str(&state.close());
+
+        lines.push(Cow::Owned(buf));
+        pos = end;
+    }
+    lines
+}
+
+/// Paints `s` with `style`, re-applying it after every reset contained in `s`.
+pub(crate) fn paint(style: Style, s: &str) -> String {
+    if style == Style::default() || s.is_empty() {
+        return s.to_owned();
+    }
+    let prefix = style.prefix();
+    let mut buf = prefix.clone();
+    for token in tokenize(s) {
+        match token {
+            Token::Text(text) => buf.push_str(text),
+            Token::Escape(e) => {
+                buf.push_str(e);
+                if is_reset(e) {
+                    buf.push_str(&prefix);
+                }
+            }
+        }
+    }
+    buf.push_str(&style.suffix());
+    buf
+}
+
+/// The colours of the xterm palette for the 16 basic colours.
+const BASIC: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The levels of each component in the 6×6×6 colour cube of xterm-256.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Returns the colour of the xterm-256 palette with the given index.
+fn rgb_of(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC[index as usize],
+        16..=231 => {
+            let i = index as usize - 16;
+            (CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6])
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Returns the index of the nearest colour in the first `n` colours of the xterm-256 palette.
+fn nearest(rgb: (u8, u8, u8), n: usize) -> u8 {
+    (0..n)
+        .map(|i| i as u8)
+        // the basic colours vary between terminals, so they are only used when required
+        .skip(if n > 16 { 16 } else { 0 })
+        .min_by_key(|&i| distance(rgb, rgb_of(i)))
+        .unwrap_or(0)
+}
+
+/// Rewrites the parameters of an SGR sequence for a terminal supporting `colors` colours.
+fn downgrade_params(params: &str, colors: usize) -> String {
+    let params: Vec<&str> = params.split(';').collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < params.len() {
+        let target = match params[i] {
+            "38" => Some(30),
+            "48" => Some(40),
+            _ => None,
+        };
+        let component = |j: usize| params.get(j).and_then(|p| p.parse::<u8>().ok());
+        let color = match (target, params.get(i + 1).copied()) {
+            (Some(_), Some("2")) => match (component(i + 2), component(i + 3), component(i + 4)) {
+                (Some(r), Some(g), Some(b)) => Some(((r, g, b), 5)),
+                _ => None,
+            },
+            (Some(_), Some("5")) if colors == 16 => component(i + 2).map(|c| (rgb_of(c), 3)),
+            _ => None,
+        };
+        match (target, color) {
+            (Some(base), Some((rgb, len))) => {
+                let index = nearest(rgb, colors);
+                out.push(match index {
+                    0..=7 => (base + index as u32).to_string(),
+                    8..=15 => (base + 60 + index as u32 - 8).to_string(),
+                    _ => format!("{};5;{}", base + 8, index),
+                });
+                i += len;
+            }
+            _ => {
+                out.push(params[i].to_owned());
+                i += 1;
+            }
+        }
+    }
+    out.join(";")
+}
+
+/// Replaces the colours of every SGR sequence in `s` by the nearest ones among the first
+/// `colors` colours of the xterm-256 palette, which is either 16 or 256.
+pub(crate) fn downgrade(s: &str, colors: usize) -> Cow<'_, str> {
+    if !s.contains(ESC) {
+        return Cow::Borrowed(s);
+    }
+    let mut buf = String::new();
+    for token in tokenize(s) {
+        match token {
+            Token::Escape(e) => match sgr_params(e) {
+                Some(params) => {
+                    buf.push_str("\x1b[");
+                    buf.push_str(&downgrade_params(params, colors));
+                    buf.push('m');
+                }
+                None => buf.push_str(e),
+            },
+            Token::Text(text) => buf.push_str(text),
+        }
+    }
+    buf.into()
+}
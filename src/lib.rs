@@ -1,13 +1,29 @@
+#[cfg(feature = "ansi_term")]
 pub use ansi_term;
+#[cfg(feature = "regex")]
+pub use regex;
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
 pub enum Align {
     Center,
-    CenterPadded { padl: usize, padr: usize },
+    CenterPadded {
+        padl: usize,
+        padr: usize,
+    },
+    #[default]
     Left,
-    LeftPadded { padl: usize },
+    LeftPadded {
+        padl: usize,
+    },
     Right,
-    RightPadded { padr: usize },
+    RightPadded {
+        padr: usize,
+    },
+    /// Lines up the decimal separators of the column, cells without one being aligned as if
+    /// it followed their text.
+    Decimal {
+        separator: char,
+    },
 }
 impl Align {
     pub fn padding_size(&self) -> usize {
@@ -18,68 +34,228 @@ impl Align {
             Self::CenterPadded { padl, padr } => padl + padr,
             Self::LeftPadded { padl } => *padl,
             Self::RightPadded { padr } => *padr,
+            Self::Decimal { .. } => 0,
         }
     }
 }
-impl std::default::Default for Align {
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Text(String),
+    /// Text made of runs painted with different styles.
+    Spans(Vec<(Style, String)>),
+    Table(Box<Table>),
+    /// A nested table whose outer frame is fused with the borders around the cell.
+    MergedTable(Box<Table>),
+    /// A typed value, displayed according to the format of its column.
+    Value(Value),
+}
+impl CellValue {
+    /// Returns the text of the value without escape sequences, or `None` for tables.
+    pub(crate) fn text(&self) -> Option<std::borrow::Cow<'_, str>> {
+        match self {
+            CellValue::Text(text) => Some(ansi::strip(text)),
+            CellValue::Spans(spans) => Some(
+                spans
+                    .iter()
+                    .map(|(_, text)| ansi::strip(text))
+                    .collect::<String>()
+                    .into(),
+            ),
+            CellValue::Value(value) => Some(Format::default().format(value).into()),
+            CellValue::Table(_) | CellValue::MergedTable(_) => None,
+        }
+    }
+
+    /// Returns the value as a number, if it is a typed value or a text of a number.
+    pub(crate) fn number(&self) -> Option<f64> {
+        match self {
+            CellValue::Value(value) => Some(value.as_f64()),
+            _ => value::parse_number(&self.text()?),
+        }
+    }
+}
+impl Default for CellValue {
     fn default() -> Self {
-        Align::Left
+        CellValue::Text(String::new())
+    }
+}
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        CellValue::Text(s.to_owned())
+    }
+}
+impl From<String> for CellValue {
+    fn from(s: String) -> Self {
+        CellValue::Text(s)
+    }
+}
+impl From<Vec<(Style, String)>> for CellValue {
+    fn from(spans: Vec<(Style, String)>) -> Self {
+        CellValue::Spans(spans)
+    }
+}
+#[cfg(feature = "ansi_term")]
+impl<'a> From<ansi_term::ANSIStrings<'a>> for CellValue {
+    fn from(strings: ansi_term::ANSIStrings<'a>) -> Self {
+        CellValue::Spans(
+            strings
+                .0
+                .iter()
+                .map(|s| ((*s.style_ref()).into(), (**s).to_owned()))
+                .collect(),
+        )
+    }
+}
+impl From<Value> for CellValue {
+    fn from(value: Value) -> Self {
+        CellValue::Value(value)
+    }
+}
+impl From<Table> for CellValue {
+    fn from(t: Table) -> Self {
+        CellValue::Table(Box::new(t))
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A cell of a table.
+///
+/// The alignment and style of a cell are applied on top of the defaults of its row, its column
+/// and the table, in this order of precedence. Cells aligned by none of them are aligned
+/// according to the content of the body of their column.
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Cell {
-    pub value: String,
-    pub align: Align,
-    pub style: ansi_term::Style,
+    pub value: CellValue,
+    /// The alignment, or `None` to inherit it.
+    pub align: Option<Align>,
+    pub style: Style,
+    /// The URI the text of the cell links to, as an OSC 8 hyperlink.
+    pub hyperlink: Option<String>,
 }
 
-use ansi_term::Style;
 impl Cell {
+    /// Creates a cell inheriting its alignment and style.
+    pub fn new<T: std::fmt::Display>(val: T) -> Self {
+        Cell {
+            value: val.to_string().into(),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
     pub fn left<T: std::fmt::Display>(val: T) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Left,
+            value: val.to_string().into(),
+            align: Some(Align::Left),
             style: Style::default(),
+            hyperlink: None,
         }
     }
     pub fn left_with_style<T: std::fmt::Display>(val: T, style: Style) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Left,
+            value: val.to_string().into(),
+            align: Some(Align::Left),
             style,
+            hyperlink: None,
         }
     }
 
     pub fn right<T: std::fmt::Display>(val: T) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Right,
+            value: val.to_string().into(),
+            align: Some(Align::Right),
             style: Style::default(),
+            hyperlink: None,
         }
     }
     pub fn right_with_style<T: std::fmt::Display>(val: T, style: Style) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Right,
+            value: val.to_string().into(),
+            align: Some(Align::Right),
             style,
+            hyperlink: None,
         }
     }
 
     pub fn center<T: std::fmt::Display>(val: T) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Center,
+            value: val.to_string().into(),
+            align: Some(Align::Center),
             style: Style::default(),
+            hyperlink: None,
         }
     }
     pub fn center_with_style<T: std::fmt::Display>(val: T, style: Style) -> Self {
         Cell {
-            value: val.to_string(),
-            align: Align::Center,
+            value: val.to_string().into(),
+            align: Some(Align::Center),
             style,
+            hyperlink: None,
         }
     }
+
+    /// Creates a cell keeping a typed value, displayed according to the format of its column.
+    pub fn value<T: Into<Value>>(value: T) -> Self {
+        Cell {
+            value: CellValue::Value(value.into()),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
+    /// Creates a cell whose text is made of differently styled runs.
+    pub fn spans<T: Into<CellValue>>(spans: T) -> Self {
+        Cell {
+            value: spans.into(),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
+    /// Creates a cell from text with inline markup such as `**bold**`, `*italic*`,
+    /// `~~strike~~`, `` `code` `` and `[red]colours[/]`; a backslash escapes the next character.
+    ///
+    /// If the markup is malformed, the text is shown as is.
+    pub fn markup(text: &str) -> Self {
+        Cell {
+            value: markup::parse(text).map_or_else(|| text.into(), CellValue::Spans),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
+    /// Creates a cell containing another table, which is rendered inside the cell's box.
+    pub fn table(table: Table) -> Self {
+        Cell {
+            value: table.into(),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
+    /// Creates a cell containing another table whose outer frame is drawn on the borders
+    /// around the cell, so that its inner borders join the borders of the outer table.
+    ///
+    /// The cell should not be padded for the frames to line up.
+    pub fn merged_table(table: Table) -> Self {
+        Cell {
+            value: CellValue::MergedTable(Box::new(table)),
+            align: None,
+            style: Style::default(),
+            hyperlink: None,
+        }
+    }
+
+    /// Makes the text of the cell a hyperlink to `uri`. This has no effect on nested tables.
+    pub fn with_hyperlink<T: Into<String>>(mut self, uri: T) -> Self {
+        self.hyperlink = Some(uri.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -96,26 +272,40 @@ pub enum CellSize {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Row {
-    HorizontalBorder(Border),
-    Cells { height: CellSize, cells: Vec<Cell> },
+    HorizontalBorder(Border, Style),
+    Cells {
+        height: CellSize,
+        cells: Vec<Cell>,
+        /// The alignment inherited by the cells.
+        align: Option<Align>,
+        /// The style inherited by the cells.
+        style: Style,
+    },
 }
 impl Row {
     pub fn flexible_height(cells: Vec<Cell>) -> Self {
         Self::Cells {
             height: CellSize::Flexible,
             cells,
+            align: None,
+            style: Style::default(),
         }
     }
     pub fn fixed_height(height: usize, cells: Vec<Cell>) -> Self {
         Self::Cells {
             height: CellSize::Fixed(height),
             cells,
+            align: None,
+            style: Style::default(),
         }
     }
+    pub fn styled_border(border: Border, style: Style) -> Self {
+        Self::HorizontalBorder(border, style)
+    }
 
     pub fn border(&self) -> Option<Border> {
         match self {
-            Row::HorizontalBorder(b) => Some(*b),
+            Row::HorizontalBorder(b, _) => Some(*b),
             _ => None,
         }
     }
@@ -125,45 +315,98 @@ impl Row {
             _ => None,
         }
     }
+
+    /// Sets the alignment inherited by the cells. This has no effect on borders.
+    pub fn with_align(mut self, align: Align) -> Self {
+        if let Row::Cells { align: a, .. } = &mut self {
+            *a = Some(align);
+        }
+        self
+    }
+    /// Sets the style inherited by the cells, or the style of the border.
+    pub fn with_style(mut self, style: Style) -> Self {
+        match &mut self {
+            Row::HorizontalBorder(_, s) | Row::Cells { style: s, .. } => *s = style,
+        }
+        self
+    }
 }
 impl From<Border> for Row {
     fn from(b: Border) -> Self {
-        Row::HorizontalBorder(b)
+        Row::HorizontalBorder(b, Style::default())
     }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum Column {
-    VerticalBorder(Border),
-    Cells { width: CellSize },
+    VerticalBorder(Border, Style),
+    Cells {
+        width: CellSize,
+        /// The alignment inherited by the cells.
+        align: Option<Align>,
+        /// The style inherited by the cells.
+        style: Style,
+    },
 }
 impl Column {
     pub fn flexible_width() -> Self {
         Self::Cells {
             width: CellSize::Flexible,
+            align: None,
+            style: Style::default(),
         }
     }
     pub fn fixed_width(width: usize) -> Self {
         Self::Cells {
             width: CellSize::Fixed(width),
+            align: None,
+            style: Style::default(),
         }
     }
+    pub fn styled_border(border: Border, style: Style) -> Self {
+        Self::VerticalBorder(border, style)
+    }
 
     pub fn border(&self) -> Option<Border> {
         match self {
-            Column::VerticalBorder(b) => Some(*b),
+            Column::VerticalBorder(b, _) => Some(*b),
             _ => None,
         }
     }
+
+    /// Sets the alignment inherited by the cells. This has no effect on borders.
+    pub fn with_align(mut self, align: Align) -> Self {
+        if let Column::Cells { align: a, .. } = &mut self {
+            *a = Some(align);
+        }
+        self
+    }
+    /// Sets the style inherited by the cells, or the style of the border.
+    pub fn with_style(mut self, style: Style) -> Self {
+        match &mut self {
+            Column::VerticalBorder(_, s) | Column::Cells { style: s, .. } => *s = style,
+        }
+        self
+    }
 }
 impl From<Border> for Column {
     fn from(b: Border) -> Self {
-        Column::VerticalBorder(b)
+        Column::VerticalBorder(b, Style::default())
     }
 }
 
+mod ansi;
+mod markup;
+mod parse;
+mod rule;
+mod style;
 mod table;
+mod value;
+pub use parse::*;
+pub use rule::*;
+pub use style::*;
 pub use table::*;
+pub use value::*;
 
 #[cfg(test)]
 mod tests;
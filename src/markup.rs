@@ -0,0 +1,132 @@
+//! A small inline markup for styling the text of cells.
+//!
+//! | markup              | style                      |
+//! |---------------------|----------------------------|
+//! | `**bold**`          | bold                       |
+//! | `*italic*`          | italic                     |
+//! | `~~strike~~`        | strikethrough              |
+//! | `` `code` ``        | dimmed, content kept as is |
+//! | `[red]text[/]`      | foreground colour          |
+//! | `[on red]text[/]`   | background colour          |
+//!
+//! Colours are `black`, `red`, `green`, `yellow`, `blue`, `purple` (or `magenta`), `cyan` and
+//! `white`. A backslash escapes the character following it.
+
+use crate::{Color, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Marker {
+    Bold,
+    Italic,
+    Strike,
+    Tag(Style),
+}
+
+fn color(name: &str) -> Option<Color> {
+    let c = match name {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "purple" | "magenta" => Color::Purple,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Parses the content of a `[...]` tag.
+fn tag(spec: &str) -> Option<Style> {
+    match spec.strip_prefix("on ") {
+        Some(bg) => color(bg.trim()).map(|c| Style::new().on(c)),
+        None => color(spec.trim()).map(|c| Style::new().fg(c)),
+    }
+}
+
+fn style_of(markers: &[Marker]) -> Style {
+    markers
+        .iter()
+        .fold(Style::new(), |style, marker| match marker {
+            Marker::Bold => style.bold(),
+            Marker::Italic => style.italic(),
+            Marker::Strike => style.strikethrough(),
+            Marker::Tag(tag) => Style {
+                foreground: tag.foreground.or(style.foreground),
+                background: tag.background.or(style.background),
+                ..style
+            },
+        })
+}
+
+/// Appends `s` painted with `style`, extending the last run if it has the same style.
+fn push(spans: &mut Vec<(Style, String)>, style: Style, s: &str) {
+    match spans.last_mut() {
+        Some((last, text)) if *last == style => text.push_str(s),
+        _ => spans.push((style, s.to_owned())),
+    }
+}
+
+/// Parses `text` into styled runs, or returns `None` if the markup is malformed.
+pub(crate) fn parse(text: &str) -> Option<Vec<(Style, String)>> {
+    let mut spans = Vec::new();
+    let mut markers: Vec<Marker> = Vec::new();
+    let mut rest = text;
+
+    // opens `marker`, or closes it if it is the innermost one
+    let toggle = |markers: &mut Vec<Marker>, marker| {
+        if markers.last() == Some(&marker) {
+            markers.pop();
+        } else {
+            markers.push(marker);
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(r) = rest.strip_prefix('\\') {
+            let escaped = r.chars().next()?;
+            push(&mut spans, style_of(&markers), &r[..escaped.len_utf8()]);
+            rest = &r[escaped.len_utf8()..];
+        } else if let Some(r) = rest
+            .strip_prefix("**")
+            // `***` closes an innermost italic before a bold
+            .filter(|_| !(rest.starts_with("***") && markers.last() == Some(&Marker::Italic)))
+        {
+            toggle(&mut markers, Marker::Bold);
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('*') {
+            toggle(&mut markers, Marker::Italic);
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("~~") {
+            toggle(&mut markers, Marker::Strike);
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('`') {
+            let end = r.find('`')?;
+            push(&mut spans, style_of(&markers).dimmed(), &r[..end]);
+            rest = &r[end + 1..];
+        } else if let Some(r) = rest.strip_prefix("[/]") {
+            match markers.pop() {
+                Some(Marker::Tag(_)) => {}
+                _ => return None,
+            }
+            rest = r;
+        } else if let Some(style) = rest
+            .strip_prefix('[')
+            .and_then(|r| r.find(']').and_then(|end| tag(&r[..end])))
+        {
+            markers.push(Marker::Tag(style));
+            rest = &rest[rest.find(']').unwrap() + 1..];
+        } else {
+            // an unknown tag is plain text as well
+            push(&mut spans, style_of(&markers), &rest[..c.len_utf8()]);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    if markers.is_empty() {
+        Some(spans)
+    } else {
+        None
+    }
+}
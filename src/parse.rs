@@ -0,0 +1,241 @@
+//! Parsing of tables drawn with box-drawing characters.
+
+use crate::table::junction;
+use crate::*;
+
+/// An error returned when a table cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// There is no line to parse.
+    Empty,
+    /// The `line`-th line, counted from 0, is not as wide as the first one.
+    UnevenLine { line: usize },
+    /// The `line`-th line, counted from 0, starts and ends like a border row but is broken by
+    /// more than one run of text.
+    BrokenBorder { line: usize },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no table to parse"),
+            ParseError::UnevenLine { line } => {
+                write!(f, "line {} is not as wide as the first line", line)
+            }
+            ParseError::BrokenBorder { line } => {
+                write!(f, "line {} is a border broken by more than one label", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns the kinds of the vertical and horizontal lines of a box-drawing character.
+fn lines_of(c: char) -> Option<(Option<Border>, Option<Border>)> {
+    let kinds = [None, Some(Border::Single), Some(Border::Double)];
+    // the arms a line of the given kind can have on either side
+    let arms = |kind: Option<Border>| match kind {
+        None => vec![(None, None)],
+        Some(_) => vec![(kind, kind), (kind, None), (None, kind)],
+    };
+    for &vertical in &kinds {
+        for &horizontal in &kinds {
+            for &(up, down) in &arms(vertical) {
+                for &(left, right) in &arms(horizontal) {
+                    let glyph = junction(up, down, left, right);
+                    if glyph != " " && glyph.starts_with(c) {
+                        return Some((vertical, horizontal));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Guesses the alignment of a cell from the margins around its lines, or returns `None` if
+/// its text fills it.
+fn guess_align(margins: &[(usize, usize)]) -> Option<Align> {
+    let all = |f: fn(usize, usize) -> bool| margins.iter().all(|&(l, r)| f(l, r));
+    if all(|l, r| l == 0 && r == 0) {
+        None
+    } else if all(|l, _| l == 0) {
+        Some(Align::Left)
+    } else if all(|_, r| r == 0) {
+        Some(Align::Right)
+    } else {
+        Some(Align::Center)
+    }
+}
+
+impl Table {
+    /// Parses a table drawn with the characters of [`Border::Single`] and [`Border::Double`],
+    /// as rendered by this crate.
+    ///
+    /// The columns get the widths they are drawn with and the rows flexible heights. Every
+    /// line between border rows is a row of its own, and the alignment of a cell is guessed
+    /// from its margins. The labels of border rows are kept, and styles are lost.
+    pub fn parse(text: &str) -> Result<Table, ParseError> {
+        Table::parse_with(text, false)
+    }
+
+    /// Parses a table like [`Table::parse`], but joins the lines between two border rows into
+    /// a single row, the lines of each cell making its text, for tables with wrapped cells.
+    pub fn parse_wrapped(text: &str) -> Result<Table, ParseError> {
+        Table::parse_with(text, true)
+    }
+
+    fn parse_with(text: &str, wrapped: bool) -> Result<Table, ParseError> {
+        use unicode_width::UnicodeWidthChar;
+
+        // each line as its columns, the column after a wide character holding `None`
+        let lines: Vec<Vec<Option<char>>> = text
+            .lines()
+            .map(|line| {
+                ansi::strip(line)
+                    .chars()
+                    .flat_map(|c| {
+                        let extra = c.width().unwrap_or(0).saturating_sub(1);
+                        std::iter::once(Some(c)).chain(std::iter::repeat_n(None, extra))
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = lines.first().ok_or(ParseError::Empty)?.len();
+        if let Some(line) = lines.iter().position(|line| line.len() != width) {
+            return Err(ParseError::UnevenLine { line });
+        }
+
+        let glyph = |c: Option<char>| c.and_then(lines_of);
+        // border rows are horizontal lines all along, but for a label
+        let mut border_rows: Vec<Option<(Border, Option<String>)>> = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            let kinds: Vec<Option<Border>> = line
+                .iter()
+                .map(|&c| glyph(c).and_then(|(_, h)| h))
+                .collect();
+            let kind = match (kinds.first(), kinds.last()) {
+                (Some(&Some(kind)), Some(&Some(_))) => kind,
+                _ => {
+                    border_rows.push(None);
+                    continue;
+                }
+            };
+            let gap: Vec<usize> = (0..width).filter(|&x| kinds[x].is_none()).collect();
+            let label = match (gap.first(), gap.last()) {
+                (Some(&start), Some(&end)) if end - start + 1 == gap.len() => {
+                    Some(line[start..=end].iter().flatten().collect())
+                }
+                (Some(_), Some(_)) => return Err(ParseError::BrokenBorder { line: y }),
+                _ => None,
+            };
+            border_rows.push(Some((kind, label)));
+        }
+        // border columns are vertical lines on every line of cells
+        let cell_lines: Vec<&Vec<Option<char>>> = lines
+            .iter()
+            .zip(&border_rows)
+            .filter(|(_, border)| border.is_none())
+            .map(|(line, _)| line)
+            .collect();
+        let border_cols: Vec<Option<Border>> = (0..width)
+            .map(|x| {
+                if cell_lines.is_empty() {
+                    return lines
+                        .iter()
+                        .map(|line| glyph(line[x]).and_then(|(v, _)| v))
+                        .next()?;
+                }
+                let mut kinds = cell_lines.iter().map(|line| match glyph(line[x]) {
+                    Some((v, None)) => v,
+                    _ => None,
+                });
+                let first = kinds.next().flatten();
+                if kinds.all(|v| v.is_some()) {
+                    first
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut cols = Vec::new();
+        let mut spans = Vec::new();
+        let mut x = 0;
+        while x < width {
+            match border_cols[x] {
+                Some(b) => {
+                    cols.push(b.into());
+                    x += 1;
+                }
+                None => {
+                    let start = x;
+                    while x < width && border_cols[x].is_none() {
+                        x += 1;
+                    }
+                    cols.push(Column::fixed_width(x - start));
+                    spans.push(start..x);
+                }
+            }
+        }
+        let mut table = Table::new(cols);
+
+        // the number of rows appended so far
+        let mut rows = 0;
+        let mut y = 0;
+        while y < lines.len() {
+            if let Some((b, label)) = &border_rows[y] {
+                if let Some(label) = label {
+                    table.set_border_label(rows, label.clone());
+                }
+                table.append_row((*b).into());
+                rows += 1;
+                y += 1;
+                continue;
+            }
+            let start = y;
+            y += 1;
+            while wrapped && y < lines.len() && border_rows[y].is_none() {
+                y += 1;
+            }
+            let cells = spans
+                .iter()
+                .map(|span| {
+                    let mut texts = Vec::new();
+                    let mut margins = Vec::new();
+                    for line in &lines[start..y] {
+                        let segment = &line[span.clone()];
+                        let text: String = segment.iter().flatten().collect();
+                        if text.trim().is_empty() {
+                            continue;
+                        }
+                        let spaces = |it: &mut dyn Iterator<Item = &Option<char>>| {
+                            it.take_while(|c| **c == Some(' ')).count()
+                        };
+                        margins.push((
+                            spaces(&mut segment.iter()),
+                            spaces(&mut segment.iter().rev()),
+                        ));
+                        texts.push(text.trim().to_owned());
+                    }
+                    Cell {
+                        align: guess_align(&margins),
+                        ..Cell::new(texts.join(" "))
+                    }
+                })
+                .collect();
+            table.append_row(Row::flexible_height(cells));
+            rows += 1;
+        }
+        Ok(table)
+    }
+}
+
+impl std::str::FromStr for Table {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Table::parse(s)
+    }
+}
@@ -0,0 +1,85 @@
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+
+/// A condition on the value of a cell, used to style the cells of a column conditionally.
+///
+/// Numeric conditions only match cells holding a typed value or whose text is a number.
+#[derive(Clone)]
+pub enum Condition {
+    /// The value is less than the given number.
+    LessThan(f64),
+    /// The value is greater than the given number.
+    GreaterThan(f64),
+    /// The text contains the given string.
+    Contains(String),
+    /// The text matches the regular expression. Requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    Matches(regex::Regex),
+    /// The value is among the given percentage of largest values of the column.
+    TopPercent(f64),
+    /// The value is among the given percentage of smallest values of the column.
+    BottomPercent(f64),
+    /// The text satisfies the predicate.
+    Custom(Arc<dyn Fn(&str) -> bool + Send + Sync + RefUnwindSafe>),
+}
+
+impl Condition {
+    /// Creates a condition from a predicate over the text of cells.
+    pub fn custom<F: Fn(&str) -> bool + Send + Sync + RefUnwindSafe + 'static>(f: F) -> Self {
+        Condition::Custom(Arc::new(f))
+    }
+
+    /// Returns whether a cell with the given text and numeric value satisfies the condition,
+    /// where `column` holds the numeric values of the column sorted in ascending order.
+    pub(crate) fn matches(&self, text: &str, number: Option<f64>, column: &[f64]) -> bool {
+        // the number of values among the given percentage of the column
+        let count = |percent: f64| (column.len() as f64 * percent / 100.0).ceil() as usize;
+        match self {
+            Condition::LessThan(x) => number.is_some_and(|v| v < *x),
+            Condition::GreaterThan(x) => number.is_some_and(|v| v > *x),
+            Condition::Contains(s) => text.contains(s.as_str()),
+            #[cfg(feature = "regex")]
+            Condition::Matches(re) => re.is_match(text),
+            Condition::TopPercent(p) => match count(*p) {
+                0 => false,
+                k => number.is_some_and(|v| v >= column[column.len() - k.min(column.len())]),
+            },
+            Condition::BottomPercent(p) => match count(*p) {
+                0 => false,
+                k => number.is_some_and(|v| v <= column[k.min(column.len()) - 1]),
+            },
+            Condition::Custom(f) => f(text),
+        }
+    }
+}
+
+impl std::fmt::Debug for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::LessThan(x) => f.debug_tuple("LessThan").field(x).finish(),
+            Condition::GreaterThan(x) => f.debug_tuple("GreaterThan").field(x).finish(),
+            Condition::Contains(s) => f.debug_tuple("Contains").field(s).finish(),
+            #[cfg(feature = "regex")]
+            Condition::Matches(re) => f.debug_tuple("Matches").field(re).finish(),
+            Condition::TopPercent(p) => f.debug_tuple("TopPercent").field(p).finish(),
+            Condition::BottomPercent(p) => f.debug_tuple("BottomPercent").field(p).finish(),
+            Condition::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Condition::LessThan(a), Condition::LessThan(b)) => a == b,
+            (Condition::GreaterThan(a), Condition::GreaterThan(b)) => a == b,
+            (Condition::Contains(a), Condition::Contains(b)) => a == b,
+            #[cfg(feature = "regex")]
+            (Condition::Matches(a), Condition::Matches(b)) => a.as_str() == b.as_str(),
+            (Condition::TopPercent(a), Condition::TopPercent(b)) => a == b,
+            (Condition::BottomPercent(a), Condition::BottomPercent(b)) => a == b,
+            (Condition::Custom(a), Condition::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,572 @@
+//! Colours and text attributes, independent of any terminal library.
+//!
+//! The API follows the one of `ansi_term`. Conversions from and to the types of other colour
+//! libraries are enabled by the features of the same names: `ansi_term`, `owo-colors`,
+//! `crossterm` and `termcolor`.
+
+/// A terminal colour.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Purple,
+    Cyan,
+    White,
+    /// A colour of the 256-colour palette.
+    Fixed(u8),
+    /// A 24-bit colour.
+    RGB(u8, u8, u8),
+}
+
+impl Color {
+    /// Returns a style with this foreground colour.
+    pub fn normal(self) -> Style {
+        Style::new().fg(self)
+    }
+    pub fn bold(self) -> Style {
+        self.normal().bold()
+    }
+    pub fn dimmed(self) -> Style {
+        self.normal().dimmed()
+    }
+    pub fn italic(self) -> Style {
+        self.normal().italic()
+    }
+    pub fn underline(self) -> Style {
+        self.normal().underline()
+    }
+    pub fn blink(self) -> Style {
+        self.normal().blink()
+    }
+    pub fn reverse(self) -> Style {
+        self.normal().reverse()
+    }
+    pub fn hidden(self) -> Style {
+        self.normal().hidden()
+    }
+    pub fn strikethrough(self) -> Style {
+        self.normal().strikethrough()
+    }
+    /// Returns a style with this foreground colour and the given background colour.
+    pub fn on(self, background: Color) -> Style {
+        self.normal().on(background)
+    }
+    /// Paints `text` with this foreground colour.
+    pub fn paint(self, text: &str) -> String {
+        self.normal().paint(text)
+    }
+
+    /// Appends the SGR parameters of this colour, `base` being 30 for the foreground and 40
+    /// for the background.
+    fn write_params(self, base: u8, params: &mut Vec<String>) {
+        let basic = |n: u8| (base + n).to_string();
+        params.push(match self {
+            Color::Black => basic(0),
+            Color::Red => basic(1),
+            Color::Green => basic(2),
+            Color::Yellow => basic(3),
+            Color::Blue => basic(4),
+            Color::Purple => basic(5),
+            Color::Cyan => basic(6),
+            Color::White => basic(7),
+            Color::Fixed(n) => format!("{};5;{}", base + 8, n),
+            Color::RGB(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        });
+    }
+}
+
+/// Colours and attributes of text.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub is_bold: bool,
+    pub is_dimmed: bool,
+    pub is_italic: bool,
+    pub is_underline: bool,
+    pub is_blink: bool,
+    pub is_reverse: bool,
+    pub is_hidden: bool,
+    pub is_strikethrough: bool,
+}
+
+impl Style {
+    /// Returns the style without colours nor attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn fg(self, foreground: Color) -> Self {
+        Style {
+            foreground: Some(foreground),
+            ..self
+        }
+    }
+    pub fn on(self, background: Color) -> Self {
+        Style {
+            background: Some(background),
+            ..self
+        }
+    }
+    pub fn bold(self) -> Self {
+        Style {
+            is_bold: true,
+            ..self
+        }
+    }
+    pub fn dimmed(self) -> Self {
+        Style {
+            is_dimmed: true,
+            ..self
+        }
+    }
+    pub fn italic(self) -> Self {
+        Style {
+            is_italic: true,
+            ..self
+        }
+    }
+    pub fn underline(self) -> Self {
+        Style {
+            is_underline: true,
+            ..self
+        }
+    }
+    pub fn blink(self) -> Self {
+        Style {
+            is_blink: true,
+            ..self
+        }
+    }
+    pub fn reverse(self) -> Self {
+        Style {
+            is_reverse: true,
+            ..self
+        }
+    }
+    pub fn hidden(self) -> Self {
+        Style {
+            is_hidden: true,
+            ..self
+        }
+    }
+    pub fn strikethrough(self) -> Self {
+        Style {
+            is_strikethrough: true,
+            ..self
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// Returns the escape sequence turning this style on, which is empty for the plain style.
+    pub fn prefix(&self) -> String {
+        if self.is_plain() {
+            return String::new();
+        }
+        let mut params = Vec::new();
+        let attributes = [
+            (self.is_bold, "1"),
+            (self.is_dimmed, "2"),
+            (self.is_italic, "3"),
+            (self.is_underline, "4"),
+            (self.is_blink, "5"),
+            (self.is_reverse, "7"),
+            (self.is_hidden, "8"),
+            (self.is_strikethrough, "9"),
+        ];
+        for (_, code) in attributes.iter().filter(|(on, _)| *on) {
+            params.push(code.to_string());
+        }
+        if let Some(background) = self.background {
+            background.write_params(40, &mut params);
+        }
+        if let Some(foreground) = self.foreground {
+            foreground.write_params(30, &mut params);
+        }
+        format!("\x1b[{}m", params.join(";"))
+    }
+
+    /// Returns the escape sequence turning this style off, which is empty for the plain style.
+    pub fn suffix(&self) -> String {
+        if self.is_plain() {
+            String::new()
+        } else {
+            "\x1b[0m".to_owned()
+        }
+    }
+
+    /// Paints `text` with this style.
+    pub fn paint(&self, text: &str) -> String {
+        format!("{}{}{}", self.prefix(), text, self.suffix())
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        color.normal()
+    }
+}
+
+#[cfg(feature = "ansi_term")]
+mod ansi_term_conversions {
+    use super::*;
+
+    impl From<ansi_term::Color> for Color {
+        fn from(color: ansi_term::Color) -> Self {
+            use ansi_term::Color as C;
+            match color {
+                C::Black => Color::Black,
+                C::Red => Color::Red,
+                C::Green => Color::Green,
+                C::Yellow => Color::Yellow,
+                C::Blue => Color::Blue,
+                C::Purple => Color::Purple,
+                C::Cyan => Color::Cyan,
+                C::White => Color::White,
+                C::Fixed(n) => Color::Fixed(n),
+                C::RGB(r, g, b) => Color::RGB(r, g, b),
+            }
+        }
+    }
+
+    impl From<Color> for ansi_term::Color {
+        fn from(color: Color) -> Self {
+            use ansi_term::Color as C;
+            match color {
+                Color::Black => C::Black,
+                Color::Red => C::Red,
+                Color::Green => C::Green,
+                Color::Yellow => C::Yellow,
+                Color::Blue => C::Blue,
+                Color::Purple => C::Purple,
+                Color::Cyan => C::Cyan,
+                Color::White => C::White,
+                Color::Fixed(n) => C::Fixed(n),
+                Color::RGB(r, g, b) => C::RGB(r, g, b),
+            }
+        }
+    }
+
+    impl From<ansi_term::Style> for Style {
+        fn from(style: ansi_term::Style) -> Self {
+            Style {
+                foreground: style.foreground.map(Into::into),
+                background: style.background.map(Into::into),
+                is_bold: style.is_bold,
+                is_dimmed: style.is_dimmed,
+                is_italic: style.is_italic,
+                is_underline: style.is_underline,
+                is_blink: style.is_blink,
+                is_reverse: style.is_reverse,
+                is_hidden: style.is_hidden,
+                is_strikethrough: style.is_strikethrough,
+            }
+        }
+    }
+
+    impl From<Style> for ansi_term::Style {
+        fn from(style: Style) -> Self {
+            ansi_term::Style {
+                foreground: style.foreground.map(Into::into),
+                background: style.background.map(Into::into),
+                is_bold: style.is_bold,
+                is_dimmed: style.is_dimmed,
+                is_italic: style.is_italic,
+                is_underline: style.is_underline,
+                is_blink: style.is_blink,
+                is_reverse: style.is_reverse,
+                is_hidden: style.is_hidden,
+                is_strikethrough: style.is_strikethrough,
+            }
+        }
+    }
+}
+
+/// Colours convert both ways, but `owo_colors::Style` cannot be inspected, so styles can only
+/// be converted into.
+#[cfg(feature = "owo-colors")]
+mod owo_colors_conversions {
+    use super::*;
+    use owo_colors::{AnsiColors, DynColor, DynColors, XtermColors};
+    use std::fmt;
+
+    impl From<Color> for DynColors {
+        fn from(color: Color) -> Self {
+            match color {
+                Color::Black => DynColors::Ansi(AnsiColors::Black),
+                Color::Red => DynColors::Ansi(AnsiColors::Red),
+                Color::Green => DynColors::Ansi(AnsiColors::Green),
+                Color::Yellow => DynColors::Ansi(AnsiColors::Yellow),
+                Color::Blue => DynColors::Ansi(AnsiColors::Blue),
+                Color::Purple => DynColors::Ansi(AnsiColors::Magenta),
+                Color::Cyan => DynColors::Ansi(AnsiColors::Cyan),
+                Color::White => DynColors::Ansi(AnsiColors::White),
+                Color::Fixed(n) => DynColors::Xterm(XtermColors::from(n)),
+                Color::RGB(r, g, b) => DynColors::Rgb(r, g, b),
+            }
+        }
+    }
+
+    /// The SGR parameters setting a colour as the foreground, such as `38;2;255;0;0`.
+    struct RawForeground<C>(C);
+
+    impl<C: DynColor> fmt::Display for RawForeground<C> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt_raw_ansi_fg(f)
+        }
+    }
+
+    /// The default colour of the terminal has no equivalent and becomes [`Color::White`].
+    impl From<DynColors> for Color {
+        fn from(color: DynColors) -> Self {
+            match color {
+                DynColors::Ansi(AnsiColors::Black) => Color::Black,
+                DynColors::Ansi(AnsiColors::Red) => Color::Red,
+                DynColors::Ansi(AnsiColors::Green) => Color::Green,
+                DynColors::Ansi(AnsiColors::Yellow) => Color::Yellow,
+                DynColors::Ansi(AnsiColors::Blue) => Color::Blue,
+                DynColors::Ansi(AnsiColors::Magenta) => Color::Purple,
+                DynColors::Ansi(AnsiColors::Cyan) => Color::Cyan,
+                DynColors::Ansi(AnsiColors::White | AnsiColors::Default) => Color::White,
+                DynColors::Ansi(AnsiColors::BrightBlack) => Color::Fixed(8),
+                DynColors::Ansi(AnsiColors::BrightRed) => Color::Fixed(9),
+                DynColors::Ansi(AnsiColors::BrightGreen) => Color::Fixed(10),
+                DynColors::Ansi(AnsiColors::BrightYellow) => Color::Fixed(11),
+                DynColors::Ansi(AnsiColors::BrightBlue) => Color::Fixed(12),
+                DynColors::Ansi(AnsiColors::BrightMagenta) => Color::Fixed(13),
+                DynColors::Ansi(AnsiColors::BrightCyan) => Color::Fixed(14),
+                DynColors::Ansi(AnsiColors::BrightWhite) => Color::Fixed(15),
+                DynColors::Xterm(x) => Color::Fixed(u8::from(x)),
+                DynColors::Rgb(r, g, b) => Color::RGB(r, g, b),
+                // CSS colours only expose their RGB values through their escape sequences
+                DynColors::Css(css) => {
+                    let params = RawForeground(css).to_string();
+                    let mut rgb = params.split(';').skip(2).map(|p| p.parse().unwrap_or(0));
+                    let mut next = || rgb.next().unwrap_or(0);
+                    Color::RGB(next(), next(), next())
+                }
+            }
+        }
+    }
+
+    impl From<Style> for owo_colors::Style {
+        fn from(style: Style) -> Self {
+            let mut s = owo_colors::Style::new();
+            if let Some(foreground) = style.foreground {
+                s = s.color(DynColors::from(foreground));
+            }
+            if let Some(background) = style.background {
+                s = s.on_color(DynColors::from(background));
+            }
+            let attributes = [
+                (style.is_bold, owo_colors::Style::bold as fn(_) -> _),
+                (style.is_dimmed, owo_colors::Style::dimmed),
+                (style.is_italic, owo_colors::Style::italic),
+                (style.is_underline, owo_colors::Style::underline),
+                (style.is_blink, owo_colors::Style::blink),
+                (style.is_reverse, owo_colors::Style::reversed),
+                (style.is_hidden, owo_colors::Style::hidden),
+                (style.is_strikethrough, owo_colors::Style::strikethrough),
+            ];
+            for (_, set) in attributes.iter().filter(|(on, _)| *on) {
+                s = set(s);
+            }
+            s
+        }
+    }
+}
+
+#[cfg(feature = "crossterm")]
+mod crossterm_conversions {
+    use super::*;
+    use crossterm::style::{Attribute, Attributes, Color as C, ContentStyle};
+
+    impl From<Color> for C {
+        fn from(color: Color) -> Self {
+            match color {
+                Color::Black => C::Black,
+                Color::Red => C::DarkRed,
+                Color::Green => C::DarkGreen,
+                Color::Yellow => C::DarkYellow,
+                Color::Blue => C::DarkBlue,
+                Color::Purple => C::DarkMagenta,
+                Color::Cyan => C::DarkCyan,
+                Color::White => C::Grey,
+                Color::Fixed(n) => C::AnsiValue(n),
+                Color::RGB(r, g, b) => C::Rgb { r, g, b },
+            }
+        }
+    }
+
+    /// Returns the colour, or `None` for the default colour of the terminal.
+    fn from_crossterm(color: C) -> Option<Color> {
+        let c = match color {
+            C::Reset => return None,
+            C::Black => Color::Black,
+            C::DarkRed => Color::Red,
+            C::DarkGreen => Color::Green,
+            C::DarkYellow => Color::Yellow,
+            C::DarkBlue => Color::Blue,
+            C::DarkMagenta => Color::Purple,
+            C::DarkCyan => Color::Cyan,
+            C::Grey => Color::White,
+            C::DarkGrey => Color::Fixed(8),
+            C::Red => Color::Fixed(9),
+            C::Green => Color::Fixed(10),
+            C::Yellow => Color::Fixed(11),
+            C::Blue => Color::Fixed(12),
+            C::Magenta => Color::Fixed(13),
+            C::Cyan => Color::Fixed(14),
+            C::White => Color::Fixed(15),
+            C::AnsiValue(n) => Color::Fixed(n),
+            C::Rgb { r, g, b } => Color::RGB(r, g, b),
+        };
+        Some(c)
+    }
+
+    const ATTRIBUTES: [Attribute; 8] = [
+        Attribute::Bold,
+        Attribute::Dim,
+        Attribute::Italic,
+        Attribute::Underlined,
+        Attribute::SlowBlink,
+        Attribute::Reverse,
+        Attribute::Hidden,
+        Attribute::CrossedOut,
+    ];
+
+    impl From<Style> for ContentStyle {
+        fn from(style: Style) -> Self {
+            let flags = [
+                style.is_bold,
+                style.is_dimmed,
+                style.is_italic,
+                style.is_underline,
+                style.is_blink,
+                style.is_reverse,
+                style.is_hidden,
+                style.is_strikethrough,
+            ];
+            let mut attributes = Attributes::default();
+            for (attribute, _) in ATTRIBUTES.iter().zip(flags.iter()).filter(|(_, on)| **on) {
+                attributes.set(*attribute);
+            }
+            ContentStyle {
+                foreground_color: style.foreground.map(Into::into),
+                background_color: style.background.map(Into::into),
+                attributes,
+                ..ContentStyle::default()
+            }
+        }
+    }
+
+    impl From<ContentStyle> for Style {
+        fn from(style: ContentStyle) -> Self {
+            let has = |i: usize| style.attributes.has(ATTRIBUTES[i]);
+            Style {
+                foreground: style.foreground_color.and_then(from_crossterm),
+                background: style.background_color.and_then(from_crossterm),
+                is_bold: has(0),
+                is_dimmed: has(1),
+                is_italic: has(2),
+                is_underline: has(3),
+                is_blink: has(4) || style.attributes.has(Attribute::RapidBlink),
+                is_reverse: has(5),
+                is_hidden: has(6),
+                is_strikethrough: has(7),
+            }
+        }
+    }
+}
+
+/// `termcolor` has no blinking, reversed nor hidden text, which are lost in conversions.
+#[cfg(feature = "termcolor")]
+mod termcolor_conversions {
+    use super::*;
+    use termcolor::{Color as C, ColorSpec};
+
+    impl From<Color> for C {
+        fn from(color: Color) -> Self {
+            match color {
+                Color::Black => C::Black,
+                Color::Red => C::Red,
+                Color::Green => C::Green,
+                Color::Yellow => C::Yellow,
+                Color::Blue => C::Blue,
+                Color::Purple => C::Magenta,
+                Color::Cyan => C::Cyan,
+                Color::White => C::White,
+                Color::Fixed(n) => C::Ansi256(n),
+                Color::RGB(r, g, b) => C::Rgb(r, g, b),
+            }
+        }
+    }
+
+    /// Returns the colour, the basic ones being replaced by their bright variants if `intense`.
+    fn from_termcolor(color: C, intense: bool) -> Color {
+        let basic = |n: u8| {
+            if intense {
+                Color::Fixed(n + 8)
+            } else {
+                [
+                    Color::Black,
+                    Color::Red,
+                    Color::Green,
+                    Color::Yellow,
+                    Color::Blue,
+                    Color::Purple,
+                    Color::Cyan,
+                    Color::White,
+                ][n as usize]
+            }
+        };
+        match color {
+            C::Black => basic(0),
+            C::Red => basic(1),
+            C::Green => basic(2),
+            C::Yellow => basic(3),
+            C::Blue => basic(4),
+            C::Magenta => basic(5),
+            C::Cyan => basic(6),
+            C::White => basic(7),
+            C::Ansi256(n) => Color::Fixed(n),
+            C::Rgb(r, g, b) => Color::RGB(r, g, b),
+            _ => Color::White,
+        }
+    }
+
+    impl From<Style> for ColorSpec {
+        fn from(style: Style) -> Self {
+            let mut spec = ColorSpec::new();
+            spec.set_fg(style.foreground.map(Into::into))
+                .set_bg(style.background.map(Into::into))
+                .set_bold(style.is_bold)
+                .set_dimmed(style.is_dimmed)
+                .set_italic(style.is_italic)
+                .set_underline(style.is_underline)
+                .set_strikethrough(style.is_strikethrough);
+            spec
+        }
+    }
+
+    impl From<&ColorSpec> for Style {
+        fn from(spec: &ColorSpec) -> Self {
+            let color = |c: Option<&C>| c.map(|c| from_termcolor(*c, spec.intense()));
+            Style {
+                foreground: color(spec.fg()),
+                background: color(spec.bg()),
+                is_bold: spec.bold(),
+                is_dimmed: spec.dimmed(),
+                is_italic: spec.italic(),
+                is_underline: spec.underline(),
+                is_strikethrough: spec.strikethrough(),
+                ..Style::default()
+            }
+        }
+    }
+}
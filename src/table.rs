@@ -1,9 +1,73 @@
 use super::*;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// The direction in which rows are sorted.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Whether a table is rendered with its styles or as plain text.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Styled, except while the `NO_COLOR` environment variable is set, or when written with
+    /// [`Table::write_to`] or [`Table::write_styled_if`] to an output that is not a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// The colours a terminal supports. Colours a terminal does not support are rendered as the
+/// nearest ones it does.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    /// 24-bit colours.
+    #[default]
+    TrueColor,
+    /// The xterm-256 palette.
+    Ansi256,
+    /// The 8 basic colours and their bright variants.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Guesses the colours supported by the terminal from the `COLORTERM` and `TERM`
+    /// environment variables.
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let colorterm = var("COLORTERM");
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if var("TERM").contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Table {
     cols: Vec<Column>,
     rows: Vec<Row>,
+    border_style: Style,
+    default_align: Option<Align>,
+    default_style: Style,
+    header_rows: usize,
+    footer_rows: usize,
+    stripe_period: usize,
+    stripe_styles: Vec<Style>,
+    rules: Vec<(usize, Condition, Style)>,
+    formats: Vec<(usize, Format)>,
+    labels: Vec<(usize, String)>,
+    background_fill: bool,
+    color_mode: ColorMode,
+    color_depth: ColorDepth,
 }
 
 impl Table {
@@ -11,286 +75,1495 @@ impl Table {
         Self {
             cols: columns,
             rows: Vec::new(),
+            border_style: Style::default(),
+            default_align: None,
+            default_style: Style::default(),
+            header_rows: 0,
+            footer_rows: 0,
+            stripe_period: 1,
+            stripe_styles: Vec::new(),
+            rules: Vec::new(),
+            formats: Vec::new(),
+            labels: Vec::new(),
+            background_fill: false,
+            color_mode: ColorMode::Auto,
+            color_depth: ColorDepth::TrueColor,
         }
     }
     pub fn append_row(&mut self, row: Row) {
         self.rows.push(row);
     }
 
+    /// Sets the style of all borders, on top of which the styles of border rows and columns
+    /// are applied.
+    pub fn set_border_style(&mut self, style: Style) {
+        self.border_style = style;
+    }
+
+    /// Sets the alignment of the cells that neither they, their row nor their column set.
+    pub fn set_default_align(&mut self, align: Align) {
+        self.default_align = Some(align);
+    }
+
+    /// Sets the style on top of which the styles of columns, rows and cells are applied.
+    pub fn set_default_style(&mut self, style: Style) {
+        self.default_style = style;
+    }
+
+    /// Marks the first `n` rows, borders included, as the header of the table.
+    pub fn set_header_rows(&mut self, n: usize) {
+        self.header_rows = n;
+    }
+
+    /// Marks the last `n` rows, borders included, as the footer of the table.
+    pub fn set_footer_rows(&mut self, n: usize) {
+        self.footer_rows = n;
+    }
+
+    /// Returns the range of rows between the header and the footer.
+    fn body(&self) -> Range<usize> {
+        let end = self.rows.len().saturating_sub(self.footer_rows);
+        self.header_rows.min(end)..end
+    }
+
+    /// Stripes the rows of cells in the body: every `period` rows, the next style of `styles`
+    /// is applied to the whole area of their cells, padding included.
+    ///
+    /// The style of a stripe is applied on top of the style of the columns, and below the
+    /// style of the rows and cells.
+    pub fn set_stripes(&mut self, period: usize, styles: Vec<Style>) {
+        assert!(period > 0, "the period of stripes must be positive");
+        self.stripe_period = period;
+        self.stripe_styles = styles;
+    }
+
+    /// Returns the style of the stripe each row belongs to.
+    fn stripes(&self) -> Vec<Style> {
+        let body = self.body();
+        let mut nth = 0;
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(ri, row)| match row {
+                Row::Cells { .. } if body.contains(&ri) && !self.stripe_styles.is_empty() => {
+                    let k = nth / self.stripe_period % self.stripe_styles.len();
+                    nth += 1;
+                    self.stripe_styles[k]
+                }
+                _ => Style::default(),
+            })
+            .collect()
+    }
+
+    /// Paints the background of each cell over its whole area, padding and lines below its
+    /// content included, instead of only behind its text.
+    pub fn set_background_fill(&mut self, enabled: bool) {
+        self.background_fill = enabled;
+    }
+
+    /// Sets whether the table is rendered with its styles. In plain text, every escape
+    /// sequence is left out and the layout is unchanged.
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+    }
+
+    /// Sets the colours supported by the terminal, to which the colours of every cell and
+    /// border are downgraded.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.color_depth = depth;
+    }
+
+    /// Returns whether styles are rendered, `terminal` telling whether the output is one when
+    /// writing to an `io::Write`.
+    fn colored(&self, terminal: Option<bool>) -> bool {
+        match self.color_mode {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                terminal.unwrap_or(true)
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        }
+    }
+
+    /// Adds a rule applying `style` to the cells of the body in the `column`-th column of
+    /// cells (borders are not counted) whose value satisfies `condition`.
+    ///
+    /// The style is applied on top of the style the cell would have otherwise, and rules added
+    /// later take precedence.
+    pub fn add_rule(&mut self, column: usize, condition: Condition, style: Style) {
+        self.rules.push((column, condition, style));
+    }
+
+    /// Sorts the rows of the body by the `column`-th column of cells, borders excluded.
+    ///
+    /// Numbers and typed values are compared by value and come before text, which is compared
+    /// character by character, and rows lacking the cell come last, in either order. Border
+    /// rows, the header and the footer stay in place, and nothing moves if there is no such
+    /// column.
+    pub fn sort_by_column(&mut self, column: usize, order: Order) {
+        self.sort_by_column_with(column, order, |a, b| a.cmp(b));
+    }
+
+    /// Sorts the rows of the body like [`Table::sort_by_column`], comparing the runs of digits
+    /// within text by their numeric value, so that `file2` comes before `file10`.
+    pub fn sort_by_column_natural(&mut self, column: usize, order: Order) {
+        self.sort_by_column_with(column, order, natural_cmp);
+    }
+
+    fn sort_by_column_with(
+        &mut self,
+        column: usize,
+        order: Order,
+        compare_text: fn(&str, &str) -> Ordering,
+    ) {
+        // only values of the same kind are compared in the given order
+        let directed = |ordering: Ordering| match order {
+            Order::Ascending => ordering,
+            Order::Descending => ordering.reverse(),
+        };
+        self.sort_by(|a, b| match (a.get(column), b.get(column)) {
+            (Some(Cell { value: a, .. }), Some(Cell { value: b, .. })) => {
+                match (a.number(), b.number()) {
+                    (Some(x), Some(y)) => directed(x.partial_cmp(&y).unwrap_or(Ordering::Equal)),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => directed(compare_text(
+                        &a.text().unwrap_or_default(),
+                        &b.text().unwrap_or_default(),
+                    )),
+                }
+            }
+            // rows lacking the cell come last
+            (a, b) => a.is_none().cmp(&b.is_none()),
+        });
+    }
+
+    /// Sorts the rows of the body with a comparator of their cells, keeping the order of equal
+    /// rows. Border rows, the header and the footer stay in place.
+    pub fn sort_by<F: FnMut(&[Cell], &[Cell]) -> Ordering>(&mut self, mut compare: F) {
+        let body = self.body();
+        let slots: Vec<usize> = body.filter(|&ri| self.rows[ri].cells().is_some()).collect();
+        let mut rows: Vec<Row> = slots.iter().map(|&ri| self.rows[ri].clone()).collect();
+        rows.sort_by(|a, b| compare(a.cells().unwrap(), b.cells().unwrap()));
+        for (ri, row) in slots.into_iter().zip(rows) {
+            self.rows[ri] = row;
+        }
+    }
+
+    /// Returns a copy of the table with the given columns of cells, borders excluded, in the
+    /// given order.
+    ///
+    /// Each column keeps the borders on its right where possible, and the borders around the
+    /// dropped columns are dropped with them. Indices past the last column are ignored.
+    pub fn select_columns(&self, columns: &[usize]) -> Table {
+        let data: Vec<usize> = (0..self.cols.len())
+            .filter(|&ci| self.cols[ci].border().is_none())
+            .collect();
+        let n = data.len();
+        let columns: Vec<usize> = columns.iter().copied().filter(|&k| k < n).collect();
+        let leading = 0..data.first().copied().unwrap_or(self.cols.len());
+        let trailing = data.last().map_or(self.cols.len(), |ci| ci + 1)..self.cols.len();
+        // the borders between the `k`-th and the next column of cells
+        let inner = |k: usize| {
+            if k + 1 < n {
+                data[k] + 1..data[k + 1]
+            } else {
+                0..0
+            }
+        };
+
+        let mut cols = self.cols[leading].to_vec();
+        for (i, &k) in columns.iter().enumerate() {
+            if i > 0 {
+                let prev = columns[i - 1];
+                let borders = if prev + 1 < n {
+                    inner(prev)
+                } else if k > 0 {
+                    inner(k - 1)
+                } else {
+                    inner(0)
+                };
+                cols.extend_from_slice(&self.cols[borders]);
+            }
+            cols.push(self.cols[data[k]].clone());
+        }
+        cols.extend_from_slice(&self.cols[trailing]);
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Cells {
+                    height,
+                    cells,
+                    align,
+                    style,
+                } => {
+                    // cells lacking in the middle of the row are replaced with empty ones
+                    let len = columns
+                        .iter()
+                        .rposition(|&k| k < cells.len())
+                        .map_or(0, |i| i + 1);
+                    let cells = columns[..len]
+                        .iter()
+                        .map(|&k| cells.get(k).cloned().unwrap_or_default())
+                        .collect();
+                    Row::Cells {
+                        height: *height,
+                        cells,
+                        align: *align,
+                        style: *style,
+                    }
+                }
+                Row::HorizontalBorder(..) => row.clone(),
+            })
+            .collect();
+
+        // settings of columns follow them
+        let mut rules = Vec::new();
+        let mut formats = Vec::new();
+        for (new, &old) in columns.iter().enumerate() {
+            rules.extend(
+                self.rules
+                    .iter()
+                    .filter(|(column, ..)| *column == old)
+                    .map(|(_, condition, style)| (new, condition.clone(), *style)),
+            );
+            formats.extend(
+                self.formats
+                    .iter()
+                    .filter(|(column, _)| *column == old)
+                    .map(|(_, format)| (new, *format)),
+            );
+        }
+
+        Table {
+            cols,
+            rows,
+            rules,
+            formats,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the table with its rows turned into columns and its columns into rows, border
+    /// rows becoming border columns and vice versa.
+    ///
+    /// Cells keep their alignment and style, and rows and columns of cells pass theirs on to
+    /// each other. Every size becomes flexible. The header, footer, stripes, rules and formats
+    /// are tied to rows or columns and are not kept.
+    pub fn transpose(&self) -> Table {
+        let cols = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::HorizontalBorder(b, style) => Column::VerticalBorder(*b, *style),
+                Row::Cells { align, style, .. } => Column::Cells {
+                    width: CellSize::Flexible,
+                    align: *align,
+                    style: *style,
+                },
+            })
+            .collect();
+
+        let mut k = 0;
+        let rows = self
+            .cols
+            .iter()
+            .map(|col| match col {
+                Column::VerticalBorder(b, style) => Row::HorizontalBorder(*b, *style),
+                Column::Cells { align, style, .. } => {
+                    let column: Vec<Option<&Cell>> = self
+                        .rows
+                        .iter()
+                        .filter_map(|row| row.cells())
+                        .map(|cells| cells.get(k))
+                        .collect();
+                    k += 1;
+                    // cells lacking in the middle of the row are replaced with empty ones
+                    let len = column
+                        .iter()
+                        .rposition(Option::is_some)
+                        .map_or(0, |i| i + 1);
+                    Row::Cells {
+                        height: CellSize::Flexible,
+                        cells: column[..len]
+                            .iter()
+                            .map(|cell| cell.cloned().unwrap_or_default())
+                            .collect(),
+                        align: *align,
+                        style: *style,
+                    }
+                }
+            })
+            .collect();
+
+        Table {
+            cols,
+            rows,
+            header_rows: 0,
+            footer_rows: 0,
+            stripe_period: 1,
+            stripe_styles: Vec::new(),
+            rules: Vec::new(),
+            formats: Vec::new(),
+            labels: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the table in a record layout, like the expanded display of `psql`: every row of
+    /// the body becomes a block of pairs of a header and a value, below a border labelled with
+    /// the number of the record.
+    ///
+    /// The headers are the cells of the last row of cells of the header, or the numbers of the
+    /// columns if there is no header.
+    pub fn records(&self) -> Table {
+        let is_border = |col: &&Column| col.border().is_some();
+        let data: Vec<usize> = (0..self.cols.len())
+            .filter(|&ci| self.cols[ci].border().is_none())
+            .collect();
+        let separator = match (data.first(), data.last()) {
+            (Some(&first), Some(&last)) => self.cols[first..last].iter().find(is_border).cloned(),
+            _ => None,
+        };
+        let mut cols = Vec::new();
+        cols.extend(self.cols.first().filter(is_border).cloned());
+        cols.push(Column::flexible_width().with_align(Align::Left));
+        cols.push(separator.unwrap_or_else(|| Border::Single.into()));
+        cols.push(Column::flexible_width().with_align(Align::Left));
+        cols.extend(self.cols.iter().skip(1).last().filter(is_border).cloned());
+
+        let headers = self.rows[..self.header_rows.min(self.rows.len())]
+            .iter()
+            .rev()
+            .find_map(Row::cells);
+        let body = self.body();
+        let border = |row: Option<&Row>| match row {
+            Some(row @ Row::HorizontalBorder(..)) => row.clone(),
+            _ => Border::Single.into(),
+        };
+        let top = border(self.rows.first());
+        let separator = border(
+            self.rows[body.clone()]
+                .iter()
+                .find(|row| row.border().is_some()),
+        );
+
+        let mut table = Table {
+            cols,
+            rows: Vec::new(),
+            header_rows: 0,
+            footer_rows: 0,
+            stripe_period: 1,
+            stripe_styles: Vec::new(),
+            rules: Vec::new(),
+            formats: Vec::new(),
+            labels: Vec::new(),
+            ..self.clone()
+        };
+        let records = self.rows[body].iter().filter_map(Row::cells);
+        for (i, cells) in records.enumerate() {
+            table.set_border_label(table.rows.len(), format!("[ RECORD {} ]", i + 1));
+            table.append_row(if i == 0 {
+                top.clone()
+            } else {
+                separator.clone()
+            });
+            for k in 0..data.len() {
+                let key = match headers.and_then(|headers| headers.get(k)) {
+                    Some(header) => Cell {
+                        align: None,
+                        ..header.clone()
+                    },
+                    None => Cell::new(k + 1),
+                };
+                let mut value = cells.get(k).cloned().unwrap_or_default();
+                if let CellValue::Value(v) = &value.value {
+                    value.value = self.format(k).format(v).into();
+                }
+                value.align = None;
+                table.append_row(Row::flexible_height(vec![key, value]));
+            }
+        }
+        if self.rows.len() > 1 {
+            if let Some(row @ Row::HorizontalBorder(..)) = self.rows.last() {
+                table.append_row(row.clone());
+            }
+        }
+
+        // widens the values so that the longest label fits between a glyph on each side
+        if let Some((_, label)) = table.labels.last() {
+            let needed = label.chars().count() + 2;
+            let value =
+                table.cols.len() - 1 - table.cols.last().filter(is_border).is_some() as usize;
+            let first = table.cols.first().filter(is_border).is_some() as usize;
+            let widths = table.layout().widths;
+            let inner: usize = widths[first..=value].iter().sum();
+            if inner < needed {
+                table.cols[value] =
+                    Column::fixed_width(widths[value] + needed - inner).with_align(Align::Left);
+            }
+        }
+        table
+    }
+
+    /// Splits the table into bands of consecutive columns of cells, borders excluded, each
+    /// rendered within `max_width` columns where possible, to be printed one below the other.
+    ///
+    /// The `sticky` columns are repeated at the beginning of every band, those past the last
+    /// column being ignored. A band holds at least one other column even if it does not fit.
+    pub fn split_columns(&self, max_width: usize, sticky: &[usize]) -> Vec<Table> {
+        let n = self
+            .cols
+            .iter()
+            .filter(|col| col.border().is_none())
+            .count();
+        let sticky: Vec<usize> = sticky.iter().copied().filter(|&k| k < n).collect();
+        let others: Vec<usize> = (0..n).filter(|k| !sticky.contains(k)).collect();
+        if others.is_empty() {
+            return vec![self.select_columns(&sticky)];
+        }
+
+        let mut bands = Vec::new();
+        let mut band: Vec<usize> = sticky.to_vec();
+        let mut table: Option<Table> = None;
+        for &k in &others {
+            band.push(k);
+            let candidate = self.select_columns(&band);
+            match table.take() {
+                Some(full) if candidate.dimensions().0 > max_width => {
+                    // the column starts the next band
+                    bands.push(full);
+                    band.truncate(sticky.len());
+                    band.push(k);
+                    table = Some(self.select_columns(&band));
+                }
+                _ => table = Some(candidate),
+            }
+        }
+        bands.extend(table);
+        bands
+    }
+
+    /// Returns a copy of the table with only the rows of the body whose cells match
+    /// `predicate`.
+    ///
+    /// Border rows between the rows of the body are kept only where they still separate two
+    /// rows, and at most one between each pair.
+    pub fn filter_rows<F: FnMut(&[Cell]) -> bool>(&self, mut predicate: F) -> Table {
+        let body = self.body();
+        // the borders before the first and after the last row of cells of the body are frames
+        let first = body.clone().find(|&ri| self.rows[ri].cells().is_some());
+        let last = body.clone().rfind(|&ri| self.rows[ri].cells().is_some());
+        let inside = match (first, last) {
+            (Some(first), Some(last)) => first..last + 1,
+            _ => 0..0,
+        };
+
+        // the kept rows with their indices
+        let mut rows = Vec::new();
+        let mut separator = None;
+        let mut kept_any = false;
+        for (ri, row) in self.rows.iter().enumerate() {
+            if !inside.contains(&ri) {
+                rows.push((ri, row.clone()));
+                continue;
+            }
+            match row.cells() {
+                Some(cells) => {
+                    if predicate(cells) {
+                        if let Some(border) = separator.take().filter(|_| kept_any) {
+                            rows.push(border);
+                        }
+                        rows.push((ri, row.clone()));
+                        kept_any = true;
+                    }
+                }
+                None => {
+                    separator.get_or_insert_with(|| (ri, row.clone()));
+                }
+            }
+        }
+
+        let labels = self
+            .labels
+            .iter()
+            .filter_map(|(row, label)| {
+                let new = rows.iter().position(|(ri, _)| ri == row)?;
+                Some((new, label.clone()))
+            })
+            .collect();
+        Table {
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+            labels,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the format of the typed values in the `column`-th column of cells, borders
+    /// excluded.
+    pub fn set_format(&mut self, column: usize, format: Format) {
+        self.formats.retain(|(c, _)| *c != column);
+        self.formats.push((column, format));
+    }
+
+    fn format(&self, column: usize) -> Format {
+        self.formats
+            .iter()
+            .find(|(c, _)| *c == column)
+            .map_or_else(Format::default, |(_, format)| *format)
+    }
+
+    /// Writes `label` over the `row`-th row, borders included, which should be a border.
+    pub fn set_border_label<T: Into<String>>(&mut self, row: usize, label: T) {
+        self.labels.retain(|(r, _)| *r != row);
+        self.labels.push((row, label.into()));
+    }
+
+    /// Returns the numeric values of the body in the `column`-th column of cells, in ascending
+    /// order.
+    fn numbers(&self, column: usize) -> Vec<f64> {
+        let mut numbers: Vec<f64> = self.rows[self.body()]
+            .iter()
+            .filter_map(|row| row.cells()?.get(column)?.value.number())
+            .collect();
+        numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        numbers
+    }
+
+    /// Resolves the alignment and style of every cell, indexed by row and column.
+    ///
+    /// Cells aligned on their decimal separator are right-aligned, padded so that their
+    /// separators line up with the ones of the other cells of the column.
+    fn grid(&self) -> Vec<Vec<Option<ResolvedCell<'_>>>> {
+        let mut grid = self.resolve();
+        for ci in 0..self.cols.len() {
+            let fraction = |resolved: &ResolvedCell| match resolved.align {
+                Align::Decimal { separator } => {
+                    resolved.text().map(|text| fraction_width(&text, separator))
+                }
+                _ => None,
+            };
+            let widest = grid
+                .iter()
+                .filter_map(|row| row[ci].as_ref().and_then(fraction))
+                .max();
+            if let Some(widest) = widest {
+                for resolved in grid.iter_mut().filter_map(|row| row[ci].as_mut()) {
+                    if let Some(w) = fraction(resolved) {
+                        resolved.align = Align::RightPadded { padr: widest - w };
+                        resolved.decimal = true;
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    /// Infers the alignment of the `column`-th column of cells from its body: numbers are
+    /// aligned to the right, booleans and short tokens are centred, and text is aligned to the
+    /// left.
+    fn infer_align(&self, column: usize) -> Align {
+        let cells: Vec<&CellValue> = self.rows[self.body()]
+            .iter()
+            .filter_map(|row| Some(&row.cells()?.get(column)?.value))
+            .filter(|value| value.text().is_some_and(|text| !text.trim().is_empty()))
+            .collect();
+        let all = |f: &dyn Fn(&CellValue, &str) -> bool| {
+            !cells.is_empty() && cells.iter().all(|value| f(value, &value.text().unwrap()))
+        };
+        if all(&|value, _| value.number().is_some()) {
+            Align::Right
+        } else if all(&|_, text| is_boolean(text) || ansi::width(text.trim()) <= 3) {
+            Align::Center
+        } else {
+            Align::Left
+        }
+    }
+
+    /// Resolves the alignment and style of every cell from the ones it inherits.
+    fn resolve(&self) -> Vec<Vec<Option<ResolvedCell<'_>>>> {
+        let body = self.body();
+        let inferred: Vec<Align> = (0..self.cols.iter().filter(|c| c.border().is_none()).count())
+            .map(|k| self.infer_align(k))
+            .collect();
+        let numbers: Vec<Vec<f64>> = self
+            .cols
+            .iter()
+            .filter(|col| col.border().is_none())
+            .enumerate()
+            .map(|(k, _)| {
+                if self.rules.iter().any(|(column, ..)| *column == k) {
+                    self.numbers(k)
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect();
+
+        self.rows
+            .iter()
+            .zip(self.stripes())
+            .enumerate()
+            .map(|(ri, (row, stripe))| {
+                let (cells, row_align, row_style) = match row {
+                    Row::Cells {
+                        cells,
+                        align,
+                        style,
+                        ..
+                    } => (cells.as_slice(), *align, *style),
+                    Row::HorizontalBorder(..) => (&[][..], None, Style::default()),
+                };
+                let mut cells_iter = cells.iter().enumerate();
+                self.cols
+                    .iter()
+                    .map(|col| match col {
+                        Column::Cells { align, style, .. } => cells_iter.next().map(|(k, cell)| {
+                            let format = self.format(k);
+                            let mut resolved = ResolvedCell {
+                                cell,
+                                formatted: match &cell.value {
+                                    CellValue::Value(value) => Some(format.format(value)),
+                                    _ => None,
+                                },
+                                align: cell
+                                    .align
+                                    .or(row_align)
+                                    .or(*align)
+                                    .or(self.default_align)
+                                    .unwrap_or(inferred[k]),
+                                decimal: false,
+                                style: [*style, stripe, row_style, cell.style]
+                                    .iter()
+                                    .fold(self.default_style, |base, top| layer(base, *top)),
+                            };
+                            if body.contains(&ri) {
+                                let number = cell.value.number();
+                                let style = resolved.text().map_or(resolved.style, |text| {
+                                    self.rules
+                                        .iter()
+                                        .filter(|(column, condition, _)| {
+                                            *column == k
+                                                && condition.matches(&text, number, &numbers[k])
+                                        })
+                                        .fold(resolved.style, |base, (.., top)| layer(base, *top))
+                                });
+                                resolved.style = style;
+                            }
+                            resolved
+                        }),
+                        Column::VerticalBorder(..) => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the style of the border at the given row and column.
+    ///
+    /// Where a border row crosses a border column, the style of the row takes precedence.
+    fn border_style_at(&self, row_idx: Option<usize>, col_idx: Option<usize>) -> Style {
+        let mut style = self.border_style;
+        if let Some(Column::VerticalBorder(_, s)) = col_idx.map(|ci| &self.cols[ci]) {
+            style = layer(style, *s);
+        }
+        if let Some(Row::HorizontalBorder(_, s)) = row_idx.map(|ri| &self.rows[ri]) {
+            style = layer(style, *s);
+        }
+        style
+    }
+
     fn get_border(
         &self,
         row_idx: usize,
         col_idx: usize,
         horizontal: Option<Border>,
         vertical: Option<Border>,
-    ) -> &str {
+    ) -> &'static str {
         match (horizontal, vertical) {
             (None, None) => panic!(""),
-            (None, Some(vertical)) => match vertical {
-                Border::Single => "│",
-                Border::Double => "║",
-            },
-            (Some(horizontal), None) => match horizontal {
-                Border::Single => "─",
-                Border::Double => "═",
-            },
+            (None, Some(vertical)) => junction(Some(vertical), Some(vertical), None, None),
+            (Some(horizontal), None) => junction(None, None, Some(horizontal), Some(horizontal)),
             (Some(horizontal), Some(vertical)) => {
-                // this represents the existence of adjacent borders
-                let mut adjacent_border = 0;
-                if row_idx > 0 {
-                    adjacent_border |= 0b0001; // up
-                }
-                if row_idx + 1 < self.rows.len() {
-                    adjacent_border |= 0b0100; // right
-                }
-                if col_idx > 0 {
-                    adjacent_border |= 0b1000; // down
-                }
-                if col_idx + 1 < self.cols.len() {
-                    adjacent_border |= 0b0010; // left
-                }
-
-                use Border::*;
-                match adjacent_border {
-                    0b0110 => match (horizontal, vertical) {
-                        (Single, Single) => "┌",
-                        (Double, Double) => "╔",
-                        (Single, Double) => "╓",
-                        (Double, Single) => "╒",
-                    },
-                    0b1110 => match (horizontal, vertical) {
-                        (Single, Single) => "┬",
-                        (Double, Double) => "╦",
-                        (Single, Double) => "╥",
-                        (Double, Single) => "╤",
-                    },
-                    0b1100 => match (horizontal, vertical) {
-                        (Single, Single) => "┐",
-                        (Double, Double) => "╗",
-                        (Single, Double) => "╖",
-                        (Double, Single) => "╕",
-                    },
-                    0b0111 => match (horizontal, vertical) {
-                        (Single, Single) => "├",
-                        (Double, Double) => "╠",
-                        (Single, Double) => "╟",
-                        (Double, Single) => "╞",
-                    },
-                    0b1111 => match (horizontal, vertical) {
-                        (Single, Single) => "┼",
-                        (Double, Double) => "╬",
-                        (Single, Double) => "╫",
-                        (Double, Single) => "╪",
-                    },
-                    0b1101 => match (horizontal, vertical) {
-                        (Single, Single) => "┤",
-                        (Double, Double) => "╣",
-                        (Single, Double) => "╢",
-                        (Double, Single) => "╡",
-                    },
-                    0b0011 => match (horizontal, vertical) {
-                        (Single, Single) => "└",
-                        (Double, Double) => "╚",
-                        (Single, Double) => "╙",
-                        (Double, Single) => "╘",
-                    },
-                    0b1011 => match (horizontal, vertical) {
-                        (Single, Single) => "┴",
-                        (Double, Double) => "╩",
-                        (Single, Double) => "╨",
-                        (Double, Single) => "╧",
-                    },
-                    0b1001 => match (horizontal, vertical) {
-                        (Single, Single) => "┘",
-                        (Double, Double) => "╝",
-                        (Single, Double) => "╜",
-                        (Double, Single) => "╛",
-                    },
-                    0b0010 | 0b1000 => match (horizontal, vertical) {
-                        (Single, _) => "─",
-                        (Double, _) => "═",
-                    },
-                    0b0001 | 0b0100 => match (horizontal, vertical) {
-                        (_, Single) => "│",
-                        (_, Double) => "║",
-                    },
-                    _ => unimplemented!(),
+                // borders extend in the directions where the table continues
+                let up = Some(vertical).filter(|_| row_idx > 0);
+                let down = Some(vertical).filter(|_| row_idx + 1 < self.rows.len());
+                let left = Some(horizontal).filter(|_| col_idx > 0);
+                let right = Some(horizontal).filter(|_| col_idx + 1 < self.cols.len());
+                if up.is_none() && down.is_none() && left.is_none() && right.is_none() {
+                    return junction(None, None, Some(horizontal), Some(horizontal));
                 }
+                junction(up, down, left, right)
             }
         }
     }
+
+    /// Returns the ranges of rows and columns inside the outer frame of the table.
+    fn inner_area(&self) -> (Range<usize>, Range<usize>) {
+        let top = matches!(self.rows.first(), Some(Row::HorizontalBorder(..))) as usize;
+        let bottom = (self.rows.len() > 1
+            && matches!(self.rows.last(), Some(Row::HorizontalBorder(..))))
+            as usize;
+        let left = matches!(self.cols.first(), Some(Column::VerticalBorder(..))) as usize;
+        let right = (self.cols.len() > 1
+            && matches!(self.cols.last(), Some(Column::VerticalBorder(..))))
+            as usize;
+        (top..self.rows.len() - bottom, left..self.cols.len() - right)
+    }
+}
+
+/// Applies `top` over `base`: colours set in `top` replace those of `base`, and the other
+/// attributes are combined.
+fn layer(base: Style, top: Style) -> Style {
+    Style {
+        foreground: top.foreground.or(base.foreground),
+        background: top.background.or(base.background),
+        is_bold: base.is_bold || top.is_bold,
+        is_dimmed: base.is_dimmed || top.is_dimmed,
+        is_italic: base.is_italic || top.is_italic,
+        is_underline: base.is_underline || top.is_underline,
+        is_blink: base.is_blink || top.is_blink,
+        is_reverse: base.is_reverse || top.is_reverse,
+        is_hidden: base.is_hidden || top.is_hidden,
+        is_strikethrough: base.is_strikethrough || top.is_strikethrough,
+    }
+}
+
+/// Returns the box-drawing character joining the given arms.
+pub(crate) fn junction(
+    up: Option<Border>,
+    down: Option<Border>,
+    left: Option<Border>,
+    right: Option<Border>,
+) -> &'static str {
+    use Border::*;
+    let vertical = up.or(down);
+    let horizontal = left.or(right);
+    let (horizontal, vertical) = match (horizontal, vertical) {
+        (None, None) => return " ",
+        (None, Some(Single)) => return "│",
+        (None, Some(Double)) => return "║",
+        (Some(Single), None) => return "─",
+        (Some(Double), None) => return "═",
+        (Some(horizontal), Some(vertical)) => (horizontal, vertical),
+    };
+    let arms = (
+        up.is_some(),
+        down.is_some(),
+        left.is_some(),
+        right.is_some(),
+    );
+    match arms {
+        (false, true, false, true) => match (horizontal, vertical) {
+            (Single, Single) => "┌",
+            (Double, Double) => "╔",
+            (Single, Double) => "╓",
+            (Double, Single) => "╒",
+        },
+        (false, true, true, true) => match (horizontal, vertical) {
+            (Single, Single) => "┬",
+            (Double, Double) => "╦",
+            (Single, Double) => "╥",
+            (Double, Single) => "╤",
+        },
+        (false, true, true, false) => match (horizontal, vertical) {
+            (Single, Single) => "┐",
+            (Double, Double) => "╗",
+            (Single, Double) => "╖",
+            (Double, Single) => "╕",
+        },
+        (true, true, false, true) => match (horizontal, vertical) {
+            (Single, Single) => "├",
+            (Double, Double) => "╠",
+            (Single, Double) => "╟",
+            (Double, Single) => "╞",
+        },
+        (true, true, true, true) => match (horizontal, vertical) {
+            (Single, Single) => "┼",
+            (Double, Double) => "╬",
+            (Single, Double) => "╫",
+            (Double, Single) => "╪",
+        },
+        (true, true, true, false) => match (horizontal, vertical) {
+            (Single, Single) => "┤",
+            (Double, Double) => "╣",
+            (Single, Double) => "╢",
+            (Double, Single) => "╡",
+        },
+        (true, false, false, true) => match (horizontal, vertical) {
+            (Single, Single) => "└",
+            (Double, Double) => "╚",
+            (Single, Double) => "╙",
+            (Double, Single) => "╘",
+        },
+        (true, false, true, true) => match (horizontal, vertical) {
+            (Single, Single) => "┴",
+            (Double, Double) => "╩",
+            (Single, Double) => "╨",
+            (Double, Single) => "╧",
+        },
+        (true, false, true, false) => match (horizontal, vertical) {
+            (Single, Single) => "┘",
+            (Double, Double) => "╝",
+            (Single, Double) => "╜",
+            (Double, Single) => "╛",
+        },
+        // there is at least one horizontal and one vertical arm
+        _ => unreachable!(),
+    }
 }
 
 fn fill(s: &str, width: usize) -> String {
     use unicode_width::UnicodeWidthStr;
     let s_width = s.width();
-    assert!(width % s_width == 0);
+    assert!(width.is_multiple_of(s_width));
     let times = width / s_width;
     s.repeat(times)
 }
 
-impl std::fmt::Display for Table {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use unicode_width::UnicodeWidthStr;
+/// Splits `pad` spaces into the left and right padding of a line aligned with `align`.
+fn split_padding(align: Align, pad: usize) -> (usize, usize) {
+    let (padl, padr) = match align {
+        Align::Center => (pad / 2, pad - pad / 2),
+        Align::Left => (0, pad),
+        Align::Right => (pad, 0),
+        Align::CenterPadded { padl, padr } => (padl + (pad - padl - padr), padr),
+        Align::LeftPadded { padl } => (padl, pad - padl),
+        Align::RightPadded { padr } => (pad - padr, padr),
+        // resolved into `RightPadded` beforehand
+        Align::Decimal { .. } => (pad, 0),
+    };
+    assert_eq!(pad, padl + padr);
+    (padl, padr)
+}
 
-        // 1. calculate each column width.
-        let widths: Vec<usize> = {
-            let mut ws = Vec::new();
-            let mut cell_idx = 0;
-            for col in self.cols.iter() {
-                match col {
-                    Column::VerticalBorder(_) => ws.push(1),
-                    Column::Cells { width } => {
-                        match width {
-                            CellSize::Flexible => {
-                                // maximum width of all rows
-                                let max_width = self
-                                    .rows
-                                    .iter()
-                                    .filter_map(|row| {
-                                        row.cells().and_then(|cells| cells.get(cell_idx))
-                                    })
-                                    .map(|cell| cell.value.width() + cell.align.padding_size())
-                                    .fold(1 /* defualt width*/, std::cmp::max);
-                                ws.push(max_width);
-                            }
-                            CellSize::Fixed(w) => ws.push(*w),
+/// Compares text, runs of digits being compared by their numeric value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    // splits off the leading run of digits or of other characters
+    fn chunk(s: &str) -> (&str, &str) {
+        let digits = s.starts_with(|c: char| c.is_ascii_digit());
+        let end = s
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(s.len());
+        s.split_at(end)
+    }
+    let (mut a, mut b) = (a, b);
+    while !a.is_empty() && !b.is_empty() {
+        let ((x, rest_a), (y, rest_b)) = (chunk(a), chunk(b));
+        let is_number = |s: &str| s.starts_with(|c: char| c.is_ascii_digit());
+        let ordering = if is_number(x) && is_number(y) {
+            let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+        } else {
+            x.cmp(y)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        a = rest_a;
+        b = rest_b;
+    }
+    a.len().cmp(&b.len())
+}
+
+fn is_boolean(text: &str) -> bool {
+    const BOOLEANS: [&str; 10] = [
+        "true", "false", "yes", "no", "y", "n", "on", "off", "✓", "✗",
+    ];
+    BOOLEANS.iter().any(|b| text.trim().eq_ignore_ascii_case(b))
+}
+
+/// Returns the width of the fractional part of `text`, its decimal separator included.
+fn fraction_width(text: &str, separator: char) -> usize {
+    text.trim_end()
+        .rfind(separator)
+        .map_or(0, |i| ansi::width(&text.trim_end()[i..]))
+}
+
+/// Wraps `text` into lines of at most `width` columns, returning each line with its width.
+fn wrap(text: &str, width: usize) -> Vec<(Cow<'_, str>, usize)> {
+    ansi::wrap(text, width)
+        .into_iter()
+        .map(|line| {
+            let w = ansi::width(&line);
+            (line, w)
+        })
+        .collect()
+}
+
+fn owned(lines: Vec<(Cow<'_, str>, usize)>) -> Vec<(Cow<'static, str>, usize)> {
+    lines
+        .into_iter()
+        .map(|(line, w)| (Cow::Owned(line.into_owned()), w))
+        .collect()
+}
+
+/// Joins styled runs into a string with escape sequences.
+fn paint_spans(spans: &[(Style, String)]) -> String {
+    spans
+        .iter()
+        .map(|(style, text)| style.paint(text))
+        .collect()
+}
+
+/// A cell with the alignment and style it inherits resolved.
+struct ResolvedCell<'a> {
+    cell: &'a Cell,
+    /// The text of a typed value, formatted with the format of its column.
+    formatted: Option<String>,
+    align: Align,
+    /// Whether the alignment lines up decimal separators.
+    decimal: bool,
+    style: Style,
+}
+
+impl ResolvedCell<'_> {
+    /// Returns the text of the cell without escape sequences, or `None` for tables.
+    fn text(&self) -> Option<Cow<'_, str>> {
+        match &self.formatted {
+            Some(text) => Some(Cow::Borrowed(text)),
+            None => self.cell.value.text(),
+        }
+    }
+
+    /// Returns the alignment of the cell in a column `width` wide. The padding lining up
+    /// decimal separators is reduced to the room left by the text, if any.
+    fn align_in(&self, width: usize) -> Align {
+        match self.align {
+            Align::RightPadded { padr } if self.decimal => {
+                let text = self.text().map_or(0, |text| ansi::width(&text));
+                match width.checked_sub(text) {
+                    Some(room) => Align::RightPadded {
+                        padr: padr.min(room),
+                    },
+                    None => Align::Right,
+                }
+            }
+            align => align,
+        }
+    }
+}
+
+/// The content of a cell laid out in its column.
+struct CellContent<'a> {
+    /// Each line of the content with its width.
+    lines: Vec<(Cow<'a, str>, usize)>,
+    /// The line filling the cell below the content.
+    blank: (Cow<'a, str>, usize),
+    /// The inner vertical borders of a merged table, as `(x-offset, border)`.
+    vertical_joints: Vec<(usize, Border)>,
+    /// The inner horizontal borders of a merged table, as `(y-offset, border)`.
+    horizontal_joints: Vec<(usize, Border)>,
+}
+
+impl<'a> CellContent<'a> {
+    fn text(lines: Vec<(Cow<'a, str>, usize)>) -> Self {
+        Self {
+            lines,
+            blank: (Cow::Borrowed(""), 0),
+            vertical_joints: Vec::new(),
+            horizontal_joints: Vec::new(),
+        }
+    }
+}
+
+/// Lays out the content of `cell` in a column of `width`.
+fn cell_content<'a>(resolved: &ResolvedCell<'a>, width: usize) -> CellContent<'a> {
+    let inner = width - resolved.align_in(width).padding_size();
+    match &resolved.cell.value {
+        CellValue::Text(text) => match &resolved.cell.hyperlink {
+            Some(uri) => CellContent::text(owned(wrap(&ansi::link(uri, text), inner))),
+            None => CellContent::text(wrap(text, inner)),
+        },
+        CellValue::Value(_) => {
+            let mut text = resolved.formatted.clone().unwrap_or_default();
+            if let Some(uri) = &resolved.cell.hyperlink {
+                text = ansi::link(uri, &text);
+            }
+            CellContent::text(owned(wrap(&text, inner)))
+        }
+        CellValue::Spans(spans) => {
+            let mut text = paint_spans(spans);
+            if let Some(uri) = &resolved.cell.hyperlink {
+                text = ansi::link(uri, &text);
+            }
+            CellContent::text(owned(wrap(&text, inner)))
+        }
+        CellValue::Table(table) => {
+            // propagate the width of this cell down to the nested table
+            let layout = table.layout_within(Some(inner));
+            let w = layout.widths.iter().sum::<usize>();
+            CellContent::text(
+                table
+                    .render(&layout)
+                    .into_iter()
+                    .map(|line| {
+                        if w > inner {
+                            (Cow::Owned(ansi::truncate(&line, inner)), inner)
+                        } else {
+                            (Cow::Owned(line), w)
                         }
-                        cell_idx += 1;
-                    }
+                    })
+                    .collect(),
+            )
+        }
+        CellValue::MergedTable(table) => {
+            // the outer frame overlaps the borders around this cell
+            let (rows, cols) = table.inner_area();
+            let frame = table.cols.len() - cols.len();
+            let layout = table.layout_within(Some(inner + frame));
+            let w = layout.widths[cols.clone()].iter().sum::<usize>().min(inner);
+
+            let lines = table
+                .render_area(&layout, rows.clone(), cols.clone())
+                .into_iter()
+                .map(|line| (Cow::Owned(ansi::truncate(&line, w)), w))
+                .collect();
+
+            // the inner vertical borders keep running below the content
+            let mut blank = String::new();
+            let mut vertical_joints = Vec::new();
+            let mut x = 0;
+            for ci in cols {
+                if let Column::VerticalBorder(b, _) = table.cols[ci] {
+                    let c = junction(Some(b), Some(b), None, None);
+                    blank.push_str(&ansi::paint(table.border_style_at(None, Some(ci)), c));
+                    vertical_joints.push((x, b));
+                } else {
+                    blank.push_str(&fill(" ", layout.widths[ci]));
                 }
+                x += layout.widths[ci];
             }
-            ws
+
+            let mut horizontal_joints = Vec::new();
+            let mut y = 0;
+            for ri in rows {
+                if let Row::HorizontalBorder(b, _) = table.rows[ri] {
+                    horizontal_joints.push((y, b));
+                }
+                y += layout.heights[ri];
+            }
+
+            vertical_joints.retain(|(x, _)| *x < w);
+            CellContent {
+                lines,
+                blank: (Cow::Owned(ansi::truncate(&blank, w)), w),
+                vertical_joints,
+                horizontal_joints,
+            }
+        }
+    }
+}
+
+/// Returns the width `cell` would take without wrapping.
+fn natural_width(resolved: &ResolvedCell) -> usize {
+    let w = match &resolved.cell.value {
+        CellValue::Text(text) => ansi::width(text),
+        CellValue::Spans(spans) => ansi::width(&paint_spans(spans)),
+        CellValue::Value(_) => ansi::width(resolved.formatted.as_deref().unwrap_or_default()),
+        CellValue::Table(table) => table.layout_within(None).widths.iter().sum(),
+        CellValue::MergedTable(table) => {
+            let (_, cols) = table.inner_area();
+            table.layout_within(None).widths[cols].iter().sum()
+        }
+    };
+    w + resolved.align.padding_size()
+}
+
+/// Sizes of every column and row, as computed before rendering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Layout {
+    widths: Vec<usize>,
+    heights: Vec<usize>,
+}
+
+impl Table {
+    /// Returns an iterator over the rendered lines, without trailing newlines.
+    pub fn lines(&self) -> impl Iterator<Item = String> {
+        self.lines_for(None)
+    }
+
+    fn lines_for(&self, terminal: Option<bool>) -> impl Iterator<Item = String> {
+        let layout = self.layout();
+        let colored = self.colored(terminal);
+        let colors = match self.color_depth {
+            ColorDepth::TrueColor => None,
+            ColorDepth::Ansi256 => Some(256),
+            ColorDepth::Ansi16 => Some(16),
         };
+        self.render(&layout)
+            .into_iter()
+            .map(move |line| match (colored, colors) {
+                (false, _) => ansi::strip(&line).into_owned(),
+                (true, Some(colors)) => ansi::downgrade(&line, colors).into_owned(),
+                (true, None) => line,
+            })
+    }
 
-        // 2. render each row
-        for (ri, row) in self.rows.iter().enumerate() {
-            match row {
-                Row::HorizontalBorder(_) => {
-                    for (ci, col) in self.cols.iter().enumerate() {
-                        let c = self.get_border(ri, ci, row.border(), col.border());
-                        write!(f, "{}", fill(c, widths[ci]))?;
-                    }
-                    writeln!(f)?;
+    /// Splits the rendered table into pages of at most `page_height` lines, returning the lines
+    /// of each page.
+    ///
+    /// Every page starts with the header rows, or the top border if there is no header, and the
+    /// borders following them, and ends with the bottom border. Rows of cells are split across
+    /// pages only if they do not fit on a page on their own.
+    ///
+    /// Every page holds at least one line of the body, so pages are taller than `page_height`
+    /// when it leaves no room for one besides the repeated rows and the bottom border.
+    pub fn paginate(&self, page_height: usize) -> Vec<Vec<String>> {
+        let heights = self.layout().heights;
+        let lines: Vec<String> = self.lines().collect();
+        let mut offsets = vec![0];
+        for h in &heights {
+            offsets.push(offsets.last().unwrap() + h);
+        }
+        let row_lines = |ri: usize| &lines[offsets[ri]..offsets[ri + 1]];
+
+        let is_border = |ri: usize| self.rows.get(ri).is_some_and(|row| row.border().is_some());
+        let mut open = if self.header_rows > 0 {
+            self.header_rows.min(self.rows.len())
+        } else {
+            is_border(0) as usize
+        };
+        // the borders between the header and the first row of cells are repeated with it
+        while open + 1 < self.rows.len() && is_border(open) {
+            open += 1;
+        }
+        let close = if self.rows.len() > open && is_border(self.rows.len() - 1) {
+            self.rows.len() - 1
+        } else {
+            self.rows.len()
+        };
+        let opening = &lines[..offsets[open]];
+        let closing = &lines[offsets[close]..];
+        let available = page_height
+            .saturating_sub(opening.len() + closing.len())
+            .max(1);
+
+        let mut pages = Vec::new();
+        let mut page: Vec<String> = Vec::new();
+        let finish = |page: &mut Vec<String>, pages: &mut Vec<Vec<String>>| {
+            let mut lines = opening.to_vec();
+            lines.append(page);
+            lines.extend_from_slice(closing);
+            pages.push(lines);
+        };
+        // a border is only put on the page if a row follows it there
+        let mut pending: &[String] = &[];
+        for ri in open..close {
+            if is_border(ri) {
+                if !page.is_empty() {
+                    pending = row_lines(ri);
                 }
-                Row::Cells { height, cells } => {
-                    use std::borrow::Cow;
-                    enum ActualContent<'a> {
-                        Border(String),
-                        Text(Vec<Cow<'a, str>>),
+                continue;
+            }
+            let mut rest = row_lines(ri);
+            if page.len() + pending.len() + rest.len() > available && !page.is_empty() {
+                finish(&mut page, &mut pages);
+                pending = &[];
+            }
+            page.extend_from_slice(pending);
+            pending = &[];
+            // a row higher than a page is split
+            while page.len() + rest.len() > available {
+                let (head, tail) = rest.split_at(available - page.len());
+                page.extend_from_slice(head);
+                finish(&mut page, &mut pages);
+                rest = tail;
+            }
+            page.extend_from_slice(rest);
+        }
+        if !page.is_empty() || pages.is_empty() {
+            finish(&mut page, &mut pages);
+        }
+        pages
+    }
+
+    /// Writes the rendered table to `w`, as plain text in [`ColorMode::Auto`] if `w` is not a
+    /// terminal.
+    pub fn write_to<W: std::io::Write + std::io::IsTerminal>(
+        &self,
+        w: &mut W,
+    ) -> std::io::Result<()> {
+        let terminal = w.is_terminal();
+        self.write_styled_if(w, terminal)
+    }
+
+    /// Writes the rendered table to `w`, as plain text in [`ColorMode::Auto`] unless
+    /// `terminal`, for outputs that cannot tell whether they are one, such as buffers.
+    pub fn write_styled_if<W: std::io::Write>(
+        &self,
+        w: &mut W,
+        terminal: bool,
+    ) -> std::io::Result<()> {
+        for line in self.lines_for(Some(terminal)) {
+            writeln!(w, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the `(width, height)` of the rendered table, in terminal columns and lines.
+    pub fn dimensions(&self) -> (usize, usize) {
+        let layout = self.layout();
+        (layout.widths.iter().sum(), layout.heights.iter().sum())
+    }
+
+    fn layout(&self) -> Layout {
+        self.layout_within(None)
+    }
+
+    /// Computes the layout, shrinking flexible columns so that the table fits in `max_width`
+    /// where possible.
+    fn layout_within(&self, max_width: Option<usize>) -> Layout {
+        let grid = self.grid();
+        let column = |ci: usize| grid.iter().filter_map(move |row| row[ci].as_ref());
+
+        // 1. calculate each column width.
+        let mut widths: Vec<usize> = self
+            .cols
+            .iter()
+            .enumerate()
+            .map(|(ci, col)| match col {
+                Column::VerticalBorder(..) => 1,
+                Column::Cells { width, .. } => match width {
+                    // maximum width of all rows
+                    CellSize::Flexible => column(ci)
+                        .map(natural_width)
+                        .fold(1 /* defualt width*/, std::cmp::max),
+                    CellSize::Fixed(w) => *w,
+                },
+            })
+            .collect();
+
+        if let Some(max_width) = max_width {
+            // the narrowest width each flexible column can be shrunk to
+            let min_widths: Vec<usize> = self
+                .cols
+                .iter()
+                .enumerate()
+                .map(|(ci, col)| match col {
+                    Column::Cells {
+                        width: CellSize::Flexible,
+                        ..
+                    } => column(ci)
+                        .map(|resolved| resolved.align.padding_size() + 1)
+                        .fold(1, std::cmp::max),
+                    _ => usize::MAX,
+                })
+                .collect();
+
+            // shrink the widest column first
+            let mut total: usize = widths.iter().sum();
+            while total > max_width {
+                let widest = (0..widths.len())
+                    .filter(|&ci| widths[ci] > min_widths[ci])
+                    .max_by_key(|&ci| widths[ci]);
+                match widest {
+                    Some(ci) => {
+                        widths[ci] -= 1;
+                        total -= 1;
                     }
-                    struct ActualCell<'a> {
-                        width: usize,
-                        align: Align,
-                        style: ansi_term::Style,
-                        content: ActualContent<'a>,
+                    None => break,
+                }
+            }
+        }
+
+        // 2. calculate each row height.
+        let heights: Vec<usize> = self
+            .rows
+            .iter()
+            .zip(grid.iter())
+            .map(|(row, cells)| match row {
+                Row::HorizontalBorder(..) => 1,
+                Row::Cells { height, .. } => match height {
+                    // maximum height of all columns
+                    CellSize::Flexible => cells
+                        .iter()
+                        .zip(widths.iter())
+                        .filter_map(|(resolved, width)| {
+                            resolved
+                                .as_ref()
+                                .map(|resolved| cell_content(resolved, *width).lines.len())
+                        })
+                        .fold(1, std::cmp::max),
+                    CellSize::Fixed(h) => *h,
+                },
+            })
+            .collect();
+
+        Layout { widths, heights }
+    }
+
+    fn render(&self, layout: &Layout) -> Vec<String> {
+        self.render_area(layout, 0..self.rows.len(), 0..self.cols.len())
+    }
+
+    /// Renders the given ranges of rows and columns.
+    fn render_area(&self, layout: &Layout, rows: Range<usize>, cols: Range<usize>) -> Vec<String> {
+        let widths = &layout.widths;
+
+        // lay out every cell first, as borders are joined with merged tables next to them
+        let contents: Vec<Vec<Option<(ResolvedCell, CellContent)>>> = self
+            .grid()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip(widths.iter())
+                    .map(|(resolved, width)| {
+                        resolved.map(|resolved| {
+                            let content = cell_content(&resolved, *width);
+                            (resolved, content)
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // returns the joints of a merged table in the given cell, relative to the column
+        let joints = |ri: usize, ci: usize, vertical: bool| -> Vec<(usize, Border)> {
+            match contents.get(ri).and_then(|row| row.get(ci)) {
+                Some(Some((resolved, content))) => {
+                    if vertical {
+                        let (padl, _) = split_padding(
+                            resolved.align_in(widths[ci]),
+                            widths[ci] - content.blank.1,
+                        );
+                        content
+                            .vertical_joints
+                            .iter()
+                            .map(|(x, b)| (padl + x, *b))
+                            .collect()
+                    } else {
+                        content.horizontal_joints.clone()
                     }
+                }
+                _ => Vec::new(),
+            }
+        };
+        let find = |joints: &[(usize, Border)], pos: usize| {
+            joints.iter().find(|(p, _)| *p == pos).map(|(_, b)| *b)
+        };
 
-                    let cells: Vec<ActualCell> = {
-                        let mut buf = Vec::new();
-                        let mut cells_iter = cells.iter();
-                        for (ci, col) in self.cols.iter().enumerate() {
-                            match col {
-                                Column::Cells { .. } => match cells_iter.next() {
-                                    Some(cell) => {
-                                        let width = widths[ci];
-                                        let wrap_opts = textwrap::Options::with_splitter(
-                                            width - cell.align.padding_size(),
-                                            textwrap::NoHyphenation,
+        let stripes = self.stripes();
+        let mut lines = Vec::new();
+        for ri in rows {
+            let row = &self.rows[ri];
+            match row {
+                Row::HorizontalBorder(h, _) => {
+                    let mut line = String::new();
+                    for ci in cols.clone() {
+                        let col = &self.cols[ci];
+                        let style = self.border_style_at(Some(ri), Some(ci));
+                        if col.border().is_some() {
+                            let c = self.get_border(ri, ci, row.border(), col.border());
+                            line.push_str(&ansi::paint(style, &fill(c, widths[ci])));
+                            continue;
+                        }
+                        let above = if ri > 0 {
+                            joints(ri - 1, ci, true)
+                        } else {
+                            Vec::new()
+                        };
+                        let below = joints(ri + 1, ci, true);
+                        let segment: String = (0..widths[ci])
+                            .map(|x| junction(find(&above, x), find(&below, x), Some(*h), Some(*h)))
+                            .collect();
+                        line.push_str(&ansi::paint(style, &segment));
+                    }
+                    if let Some((_, label)) = self.labels.iter().find(|(r, _)| *r == ri) {
+                        // the label starts after the first glyph of the first column of cells
+                        let at = cols
+                            .clone()
+                            .take_while(|&ci| self.cols[ci].border().is_some())
+                            .map(|ci| widths[ci])
+                            .sum::<usize>()
+                            + 1;
+                        // and stops before the borders closing the line
+                        let end = cols
+                            .clone()
+                            .rev()
+                            .skip_while(|&ci| self.cols[ci].border().is_some())
+                            .map(|ci| widths[ci])
+                            .sum::<usize>();
+                        let label: String = label.chars().take(end.saturating_sub(at)).collect();
+                        line = ansi::overwrite(&line, at, &label);
+                    }
+                    lines.push(line);
+                }
+                Row::Cells { .. } => {
+                    // padding is painted with the style of the stripe
+                    let stripe = stripes[ri];
+                    let pad = |style, n| ansi::paint(style, &fill(" ", n));
+                    let mut row_lines = vec![String::new(); layout.heights[ri]];
+                    for ci in cols.clone() {
+                        match &self.cols[ci] {
+                            Column::Cells { .. } => match &contents[ri][ci] {
+                                Some((resolved, content)) => {
+                                    let fill_style = if self.background_fill {
+                                        let background = Style {
+                                            background: resolved.style.background,
+                                            ..Style::default()
+                                        };
+                                        layer(stripe, background)
+                                    } else {
+                                        stripe
+                                    };
+                                    let text = content
+                                        .lines
+                                        .iter()
+                                        .chain(std::iter::repeat(&content.blank));
+                                    for (buf, (w, sz)) in row_lines.iter_mut().zip(text) {
+                                        let (padl, padr) = split_padding(
+                                            resolved.align_in(widths[ci]),
+                                            widths[ci] - sz,
                                         );
-                                        buf.push(ActualCell {
-                                            width: width + cell.align.padding_size(),
-                                            align: cell.align,
-                                            style: cell.style,
-                                            content: ActualContent::Text(textwrap::wrap(
-                                                cell.value.as_str(),
-                                                wrap_opts,
-                                            )),
-                                        });
-                                    }
-                                    None => {
-                                        // empty cell
-                                        buf.push(ActualCell {
-                                            width: widths[ci],
-                                            align: Align::default(),
-                                            style: ansi_term::Style::default(),
-                                            content: ActualContent::Text(Vec::new()),
-                                        });
+                                        buf.push_str(&pad(fill_style, padl));
+                                        buf.push_str(&ansi::paint(resolved.style, w));
+                                        buf.push_str(&pad(fill_style, padr));
                                     }
-                                },
-                                Column::VerticalBorder(b) => {
-                                    let border_str = self.get_border(ri, ci, None, Some(*b));
-                                    let repeated = fill(border_str, widths[ci]);
-                                    let align = Align::default();
-                                    buf.push(ActualCell {
-                                        width: widths[ci] + align.padding_size(),
-                                        align: Align::default(),
-                                        style: ansi_term::Style::default(),
-                                        content: ActualContent::Border(repeated),
-                                    });
                                 }
-                            }
-                        }
-                        buf
-                    };
-
-                    let height = match height {
-                        CellSize::Flexible => {
-                            // maximum width of all columns
-                            cells
-                                .iter()
-                                .filter_map(|actual| match &actual.content {
-                                    ActualContent::Text(text) => Some(text.len()),
-                                    _ => None,
-                                })
-                                .fold(1, std::cmp::max)
-                        }
-                        CellSize::Fixed(h) => *h,
-                    };
-
-                    let mut lines = vec![String::new(); height];
-                    for actual in cells {
-                        match actual.content {
-                            ActualContent::Text(text) => {
-                                let text =
-                                    text.into_iter().chain(std::iter::repeat(Cow::Borrowed("")));
-                                for (buf, w) in lines.iter_mut().zip(text) {
-                                    let sz = w.as_ref().width();
-                                    let pad = actual.width - actual.align.padding_size() - sz;
-                                    let (padl, padr) = match actual.align {
-                                        Align::Center => (pad / 2, pad - pad / 2),
-                                        Align::Left => (0, pad),
-                                        Align::Right => (pad, 0),
-                                        Align::CenterPadded { padl, padr } => {
-                                            (padl + (pad - padl - padr), padr)
-                                        }
-                                        Align::LeftPadded { padl } => (padl, pad - padl),
-                                        Align::RightPadded { padr } => (pad - padr, padr),
-                                    };
-                                    assert_eq!(pad, padl + padr);
-                                    buf.push_str(&fill(" ", padl));
-                                    buf.push_str(&actual.style.paint(w.as_ref()).to_string());
-                                    buf.push_str(&fill(" ", padr));
+                                None => {
+                                    // empty cell
+                                    for buf in row_lines.iter_mut() {
+                                        buf.push_str(&pad(stripe, widths[ci]));
+                                    }
                                 }
-                            }
-                            ActualContent::Border(border) => {
-                                for buf in lines.iter_mut() {
-                                    buf.push_str(
-                                        &actual
-                                            .style
-                                            .paint(fill(&border, actual.width))
-                                            .to_string(),
+                            },
+                            Column::VerticalBorder(b, _) => {
+                                let style = self.border_style_at(Some(ri), Some(ci));
+                                let left = if ci > 0 {
+                                    joints(ri, ci - 1, false)
+                                } else {
+                                    Vec::new()
+                                };
+                                let right = joints(ri, ci + 1, false);
+                                for (y, buf) in row_lines.iter_mut().enumerate() {
+                                    let c = junction(
+                                        Some(*b),
+                                        Some(*b),
+                                        find(&left, y),
+                                        find(&right, y),
                                     );
+                                    buf.push_str(&ansi::paint(style, &fill(c, widths[ci])));
                                 }
                             }
                         }
                     }
-                    for line in lines {
-                        writeln!(f, "{}", line)?;
-                    }
+                    lines.extend(row_lines);
                 }
             }
         }
+        lines
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
         Ok(())
     }
 }
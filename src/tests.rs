@@ -224,3 +224,1334 @@ fn test_combine_border() {
     .to_owned();
     assert_eq!(table.to_string(), expected);
 }
+
+#[test]
+fn test_lines_and_dimensions() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::fixed_width(2),
+        Border::Single.into(),
+    ]);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("abc"),
+        Cell::left("1234"),
+    ]));
+    table.append_row(Border::Single.into());
+
+    let lines: Vec<String> = table.lines().collect();
+    assert_eq!(lines, vec!["┌───┬──┐", "│abc│12│", "│   │34│", "└───┴──┘"]);
+    assert_eq!(table.dimensions(), (8, 4));
+    assert_eq!(lines.join("\n") + "\n", table.to_string());
+}
+
+#[test]
+fn test_nested_table() {
+    let mut inner = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    inner.append_row(Border::Single.into());
+    inner.append_row(Row::flexible_height(vec![
+        Cell::left("cpu"),
+        Cell::right("12%"),
+    ]));
+    inner.append_row(Border::Single.into());
+
+    let mut table = Table::new(vec![
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+    ]);
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("host1"),
+        Cell::table(inner.clone()),
+    ]));
+    table.append_row(Border::Double.into());
+    let expected = r#"╔═════╦═════════╗
+║host1║┌───┬───┐║
+║     ║│cpu│12%│║
+║     ║└───┴───┘║
+╚═════╩═════════╝
+"#
+    .to_owned();
+    assert_eq!(table.to_string(), expected);
+
+    // the width of a fixed column is propagated to the nested table
+    let mut table = Table::new(vec![
+        Border::Double.into(),
+        Column::fixed_width(7),
+        Border::Double.into(),
+    ]);
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![Cell::table(inner)]));
+    table.append_row(Border::Double.into());
+    let expected = r#"╔═══════╗
+║┌──┬──┐║
+║│cp│12│║
+║│u │ %│║
+║└──┴──┘║
+╚═══════╝
+"#
+    .to_owned();
+    assert_eq!(table.to_string(), expected);
+}
+
+#[test]
+fn test_merged_table() {
+    let mut inner = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    inner.append_row(Border::Single.into());
+    inner.append_row(Row::flexible_height(vec![
+        Cell::left("cpu"),
+        Cell::right("12%"),
+    ]));
+    inner.append_row(Border::Single.into());
+    inner.append_row(Row::flexible_height(vec![
+        Cell::left("mem"),
+        Cell::right("3%"),
+    ]));
+    inner.append_row(Border::Single.into());
+
+    let mut table = Table::new(vec![
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+    ]);
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("host1"),
+        Cell::merged_table(inner.clone()),
+    ]));
+    table.append_row(Border::Double.into());
+    table.append_row(Row::fixed_height(
+        4,
+        vec![Cell::left("host2"), Cell::merged_table(inner)],
+    ));
+    table.append_row(Border::Double.into());
+    let expected = r#"╔═════╦═══╤═══╗
+║host1║cpu│12%║
+║     ╟───┼───╢
+║     ║mem│ 3%║
+╠═════╬═══╪═══╣
+║host2║cpu│12%║
+║     ╟───┼───╢
+║     ║mem│ 3%║
+║     ║   │   ║
+╚═════╩═══╧═══╝
+"#
+    .to_owned();
+    assert_eq!(table.to_string(), expected);
+}
+
+#[test]
+fn test_escape_sequences() {
+    // escape sequences take no space
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("\x1b[31mred\x1b[0m")]));
+    table.append_row(Row::flexible_height(vec![Cell::left("plain")]));
+    table.append_row(Border::Single.into());
+    let expected = "┌─────┐\n│\x1b[31mred\x1b[0m  │\n│plain│\n└─────┘\n";
+    assert_eq!(table.to_string(), expected);
+
+    // styles and hyperlinks are re-opened after line wraps
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(3),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![Cell::left(
+        "\x1b[1mabc \x1b[4mdef\x1b[0m",
+    )]));
+    table.append_row(Row::flexible_height(vec![Cell::left(
+        "\x1b]8;;http://a\x1b\\ab cd\x1b]8;;\x1b\\",
+    )]));
+    let expected = concat!(
+        "│\x1b[1mabc\x1b[0m│\n",
+        "│\x1b[1m\x1b[4mdef\x1b[0m│\n",
+        "│\x1b]8;;http://a\x1b\\ab\x1b]8;;\x1b\\ │\n",
+        "│\x1b]8;;http://a\x1b\\cd\x1b]8;;\x1b\\ │\n",
+    );
+    assert_eq!(table.to_string(), expected);
+
+    // the style of the cell is applied again after a reset in the value
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![Cell::left_with_style(
+        "a\x1b[0mb",
+        Style::new().bold(),
+    )]));
+    assert_eq!(table.to_string(), "\x1b[1ma\x1b[0m\x1b[1mb\x1b[0m\n");
+}
+
+#[test]
+fn test_spans() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(2),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![Cell::spans(vec![
+        (Style::new().bold(), "ab cd".to_owned()),
+        (Style::new(), " ".to_owned()),
+        (Color::Red.normal(), "ef".to_owned()),
+    ])]));
+    let expected = concat!(
+        "│\x1b[1mab\x1b[0m│\n",
+        "│\x1b[1mcd\x1b[0m│\n",
+        "│\x1b[31mef\x1b[0m│\n",
+    );
+    assert_eq!(table.to_string(), expected);
+
+    let spans = vec![
+        (Color::Blue.normal(), "x".to_owned()),
+        (Style::new(), "yz".to_owned()),
+    ];
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![Cell::spans(spans)]));
+    assert_eq!(table.to_string(), "\x1b[34mx\x1b[0myz\n");
+}
+
+#[test]
+fn test_markup() {
+    let cell = Cell::markup(r"**bold *both*** `*code*` [red]red [on blue]x[/][/] \*\[red]");
+    assert_eq!(
+        cell.value,
+        CellValue::Spans(vec![
+            (Style::new().bold(), "bold ".to_owned()),
+            (Style::new().bold().italic(), "both".to_owned()),
+            (Style::new(), " ".to_owned()),
+            (Style::new().dimmed(), "*code*".to_owned()),
+            (Style::new(), " ".to_owned()),
+            (Color::Red.normal(), "red ".to_owned()),
+            (Color::Red.on(Color::Blue), "x".to_owned()),
+            (Style::new(), " *[red]".to_owned()),
+        ])
+    );
+
+    // unknown tags are plain text
+    assert_eq!(
+        Cell::markup("[1] ~x~").value,
+        CellValue::Spans(vec![(Style::new(), "[1] ~x~".to_owned())])
+    );
+
+    // malformed markup falls back to plain text
+    for text in &["**bold", "[red]x", "x[/]", "`code", r"x\"] {
+        assert_eq!(Cell::markup(text).value, CellValue::Text(text.to_string()));
+    }
+}
+
+#[test]
+fn test_styled_border() {
+    let red = Color::Red.normal();
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(1),
+        Column::styled_border(Border::Single, Style::new().bold()),
+        Column::fixed_width(1),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.set_border_style(Style::new().dimmed());
+    table.append_row(Row::styled_border(Border::Single, red));
+    table.append_row(Row::fixed_height(1, vec![Cell::left("a"), Cell::left("b")]));
+    table.append_row(Border::Single.into());
+
+    let dimmed = |s: &str| Style::new().dimmed().paint(s).to_string();
+    let expected = [
+        format!(
+            "{}{}{}{}{}",
+            red.dimmed().paint("┌"),
+            red.dimmed().paint("─"),
+            red.dimmed().bold().paint("┬"),
+            red.dimmed().paint("─"),
+            red.dimmed().paint("┐"),
+        ),
+        format!(
+            "{}a{}b{}",
+            dimmed("│"),
+            Style::new().dimmed().bold().paint("│"),
+            dimmed("│"),
+        ),
+        format!(
+            "{}{}{}{}{}",
+            dimmed("└"),
+            dimmed("─"),
+            Style::new().dimmed().bold().paint("┴"),
+            dimmed("─"),
+            dimmed("┘"),
+        ),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_default_align_and_style() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(3)
+            .with_align(Align::Right)
+            .with_style(Color::Red.normal()),
+        Border::Single.into(),
+        Column::fixed_width(3),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.set_default_align(Align::Center);
+    table.set_default_style(Style::new().italic());
+    table.append_row(Row::fixed_height(1, vec![Cell::new("a"), Cell::new("b")]));
+    table.append_row(
+        Row::fixed_height(1, vec![Cell::new("c"), Cell::new("d")])
+            .with_align(Align::Left)
+            .with_style(Color::Blue.bold()),
+    );
+    table.append_row(Row::fixed_height(
+        1,
+        vec![
+            Cell::right_with_style("e", Color::Green.normal()),
+            Cell::left("f"),
+        ],
+    ));
+
+    let italic = |s: &str| Style::new().italic().paint(s).to_string();
+    let expected = [
+        format!("│  {}│ {} │", Color::Red.italic().paint("a"), italic("b")),
+        format!(
+            "│{}  │{}  │",
+            Color::Blue.bold().italic().paint("c"),
+            Color::Blue.bold().italic().paint("d"),
+        ),
+        format!("│  {}│{}  │", Color::Green.italic().paint("e"), italic("f")),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_stripes() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(3),
+        Border::Single.into(),
+        Column::fixed_width(1),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::fixed_height(1, vec![Cell::left("h")]));
+    table.append_row(Border::Single.into());
+    for s in &["a", "b", "c", "d"] {
+        table.append_row(Row::fixed_height(1, vec![Cell::center(s)]));
+    }
+    table.append_row(Row::fixed_height(1, vec![Cell::left("f")]));
+    table.append_row(Border::Single.into());
+    table.set_header_rows(3);
+    table.set_footer_rows(2);
+
+    let grey = Style::new().on(Color::Black);
+    table.set_stripes(2, vec![Style::default(), grey]);
+    let expected = [
+        "┌───┬─┐".to_owned(),
+        "│h  │ │".to_owned(),
+        "├───┼─┤".to_owned(),
+        "│ a │ │".to_owned(),
+        "│ b │ │".to_owned(),
+        format!(
+            "│{}{}{}│{}│",
+            grey.paint(" "),
+            grey.paint("c"),
+            grey.paint(" "),
+            grey.paint(" ")
+        ),
+        format!(
+            "│{}{}{}│{}│",
+            grey.paint(" "),
+            grey.paint("d"),
+            grey.paint(" "),
+            grey.paint(" ")
+        ),
+        "│f  │ │".to_owned(),
+        "└───┴─┘".to_owned(),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_rules() {
+    let mut table = Table::new(vec![Column::flexible_width(), Column::flexible_width()]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("n"),
+        Cell::left("log"),
+    ]));
+    for (n, log) in &[("-1", "ok"), ("5", "ERROR: x"), ("10", "ok"), ("3", "ok")] {
+        table.append_row(Row::flexible_height(vec![Cell::right(n), Cell::left(log)]));
+    }
+    table.set_header_rows(1);
+    table.add_rule(0, Condition::LessThan(0.0), Color::Red.normal());
+    table.add_rule(0, Condition::TopPercent(25.0), Color::Green.normal());
+    table.add_rule(
+        1,
+        Condition::Contains("ERROR".to_owned()),
+        Color::Red.bold(),
+    );
+    table.add_rule(1, Condition::custom(|s| s == "n"), Style::new().italic());
+
+    let expected = [
+        "n log     ".to_owned(),
+        format!("{}ok      ", Color::Red.paint("-1")),
+        format!(" 5{}", Color::Red.bold().paint("ERROR: x")),
+        format!("{}ok      ", Color::Green.paint("10")),
+        " 3ok      ".to_owned(),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_rules_matches() {
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    table.set_color_mode(ColorMode::Always);
+    for log in &["ERROR: x", "no ERROR"] {
+        table.append_row(Row::flexible_height(vec![Cell::left(log)]));
+    }
+    let error = Condition::Matches(regex::Regex::new("^ERROR").unwrap());
+    table.add_rule(0, error, Color::Red.normal());
+
+    let expected = [Color::Red.paint("ERROR: x"), "no ERROR".to_owned()];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_hash_columns() {
+    let columns: std::collections::HashSet<Column> = vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Column::fixed_width(3).with_align(Align::Right),
+        Border::Single.into(),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(columns.len(), 3);
+}
+
+#[test]
+fn test_unwind_safe() {
+    fn assert_unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}
+    assert_unwind_safe::<Table>();
+    assert_unwind_safe::<Condition>();
+}
+
+#[test]
+fn test_background_fill() {
+    let blue = Style::new().on(Color::Blue);
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(3),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::fixed_height(
+        2,
+        vec![Cell::center_with_style("a", blue.bold())],
+    ));
+
+    // only the text is painted by default
+    let expected = [
+        format!("│ {} │", blue.bold().paint("a")),
+        "│   │".to_owned(),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+
+    table.set_background_fill(true);
+    let expected = [
+        format!(
+            "│{}{}{}│",
+            blue.paint(" "),
+            blue.bold().paint("a"),
+            blue.paint(" ")
+        ),
+        format!("│{}{}│", blue.paint(" "), blue.paint("  ")),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_color_mode() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(5),
+        Border::Single.into(),
+    ]);
+    table.set_border_style(Color::Red.normal());
+    table.append_row(Row::flexible_height(vec![Cell::right_with_style(
+        "a",
+        Color::Blue.bold(),
+    )]));
+    let plain = ["│    a│"];
+
+    table.set_color_mode(ColorMode::Always);
+    let colored = table.lines().collect::<Vec<_>>();
+    assert_ne!(colored, plain);
+
+    table.set_color_mode(ColorMode::Never);
+    assert_eq!(table.lines().collect::<Vec<_>>(), plain);
+
+    // styles are left out when writing to an output that is not a terminal
+    table.set_color_mode(ColorMode::Auto);
+    let mut buf = Vec::new();
+    table.write_styled_if(&mut buf, false).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "│    a│\n");
+    table.set_color_mode(ColorMode::Always);
+    let mut buf = Vec::new();
+    table.write_styled_if(&mut buf, false).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), colored[0].clone() + "\n");
+
+    // a file is not a terminal
+    table.set_color_mode(ColorMode::Auto);
+    let path = std::env::temp_dir().join(format!("table-color-mode-{}", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    table.write_to(&mut file).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written, "│    a│\n");
+}
+
+#[test]
+fn test_no_color() {
+    // the variable is only set in a child process, not to affect the tests running alongside
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    table.append_row(Row::flexible_height(vec![Cell::left_with_style(
+        "a",
+        Color::Red.normal(),
+    )]));
+    if std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) {
+        assert_eq!(table.to_string(), Color::Red.paint("a") + "\n");
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "tests::test_no_color", "--test-threads=1"])
+            .env("NO_COLOR", "1")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        return;
+    }
+
+    assert_eq!(table.to_string(), "a\n");
+    assert_eq!(table.lines().collect::<Vec<_>>(), ["a"]);
+    let mut buf = Vec::new();
+    table.write_styled_if(&mut buf, true).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "a\n");
+
+    table.set_color_mode(ColorMode::Always);
+    assert_eq!(table.to_string(), Color::Red.paint("a") + "\n");
+}
+
+#[test]
+fn test_color_depth() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(1),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.set_border_style(Color::RGB(250, 10, 10).normal());
+    table.append_row(Row::flexible_height(vec![Cell::left_with_style(
+        "a",
+        Style::new()
+            .fg(Color::RGB(0, 0, 130))
+            .on(Color::Fixed(250))
+            .bold(),
+    )]));
+
+    table.set_color_depth(ColorDepth::Ansi256);
+    let border = Color::Fixed(196).normal();
+    let cell = Style::new()
+        .fg(Color::Fixed(18))
+        .on(Color::Fixed(250))
+        .bold();
+    let expected = [format!(
+        "{}{}{}",
+        border.paint("│"),
+        cell.paint("a"),
+        border.paint("│")
+    )];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+
+    table.set_color_depth(ColorDepth::Ansi16);
+    let expected = ["\x1b[91m│\x1b[0m\x1b[1;47;34ma\x1b[0m\x1b[91m│\x1b[0m"];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[cfg(feature = "ansi_term")]
+#[test]
+fn test_ansi_term_conversions() {
+    let original = ansi_term::Color::RGB(1, 2, 3)
+        .on(ansi_term::Color::Fixed(4))
+        .bold()
+        .strikethrough();
+    let style = Style::from(original);
+    assert_eq!(
+        style,
+        Color::RGB(1, 2, 3)
+            .on(Color::Fixed(4))
+            .bold()
+            .strikethrough()
+    );
+    assert_eq!(ansi_term::Style::from(style), original);
+    assert_eq!(style.paint("x"), original.paint("x").to_string());
+}
+
+#[cfg(feature = "owo-colors")]
+#[test]
+fn test_owo_colors_conversions() {
+    use owo_colors::{AnsiColors, CssColors, DynColors, XtermColors};
+
+    for color in [Color::Purple, Color::Fixed(200), Color::RGB(1, 2, 3)] {
+        assert_eq!(Color::from(DynColors::from(color)), color);
+    }
+    let bright = DynColors::Ansi(AnsiColors::BrightRed);
+    assert_eq!(Color::from(bright), Color::Fixed(9));
+    let xterm = DynColors::Xterm(XtermColors::from(42));
+    assert_eq!(Color::from(xterm), Color::Fixed(42));
+    let css = DynColors::Css(CssColors::Orange);
+    assert_eq!(Color::from(css), Color::RGB(255, 165, 0));
+}
+
+#[test]
+fn test_hyperlink() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(4),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Row::flexible_height(vec![
+        Cell::right("ab cd").with_hyperlink("https://example.com")
+    ]));
+    let open = "\x1b]8;;https://example.com\x1b\\";
+    let close = "\x1b]8;;\x1b\\";
+    let expected = [
+        format!("│  {}ab{}│", open, close),
+        format!("│  {}cd{}│", open, close),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+    assert_eq!(table.dimensions(), (6, 2));
+}
+
+#[test]
+fn test_decimal_align() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width().with_align(Align::Decimal { separator: '.' }),
+        Border::Single.into(),
+        Column::flexible_width().with_align(Align::Decimal { separator: ',' }),
+        Border::Single.into(),
+    ]);
+    for (a, b) in [("3.14", "1,5"), ("-12", "10,25"), ("0.5", "x")] {
+        table.append_row(Row::flexible_height(vec![Cell::new(a), Cell::new(b)]));
+    }
+    let expected = concat!("│  3.14│ 1,5 │\n", "│-12   │10,25│\n", "│  0.5 │ x   │\n",);
+    assert_eq!(table.to_string(), expected);
+}
+
+#[test]
+fn test_typed_values() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let format = Format::new().thousands(',');
+    assert_eq!(format.format(&Value::Integer(-1234567)), "-1,234,567");
+    assert_eq!(
+        format.precision(2).format(&Value::Float(1234.5)),
+        "1,234.50"
+    );
+    assert_eq!(format.format(&Value::Bytes(1000)), "1,000 B");
+    let size = Value::Bytes(1288490189);
+    assert_eq!(Format::new().binary_sizes().format(&size), "1.2 GiB");
+    assert_eq!(Format::new().decimal_sizes().format(&size), "1.3 GB");
+    let duration = |secs| Value::Duration(Duration::from_secs(secs));
+    assert_eq!(Format::new().format(&duration(3723)), "1h 2m 3s");
+    assert_eq!(
+        Format::new().format(&Value::Duration(Duration::from_millis(250))),
+        "250ms"
+    );
+    let time = UNIX_EPOCH + Duration::from_secs(1_612_094_400);
+    assert_eq!(Format::new().format(&time.into()), "2021-01-31 12:00:00");
+    let relative = Format::new().relative_to(time);
+    assert_eq!(
+        relative.format(&(time - Duration::from_secs(180)).into()),
+        "3 minutes ago"
+    );
+    assert_eq!(
+        relative.format(&(time + Duration::from_secs(3600)).into()),
+        "in 1 hour"
+    );
+
+    // rules see the values rather than their text
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width().with_align(Align::Right),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.set_format(0, Format::new().thousands(','));
+    table.add_rule(0, Condition::GreaterThan(1000.0), Style::new().bold());
+    table.append_row(Row::flexible_height(vec![Cell::value(1234567)]));
+    table.append_row(Row::flexible_height(vec![Cell::value(12)]));
+    let expected = [
+        format!("│{}│", Style::new().bold().paint("1,234,567")),
+        "│       12│".to_owned(),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_inferred_align() {
+    let mut table = Table::new(vec![
+        Column::fixed_width(6),
+        Column::fixed_width(6),
+        Column::fixed_width(6),
+        Column::fixed_width(6),
+    ]);
+    table.set_header_rows(1);
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("name"),
+        Cell::new("price"),
+        Cell::new("ok"),
+        Cell::new("mixed"),
+    ]));
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("apple"),
+        Cell::new("1,200"),
+        Cell::new("yes"),
+        Cell::new("1"),
+    ]));
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("kiwi"),
+        Cell::value(-3.5),
+        Cell::new("no"),
+        Cell::new("one"),
+    ]));
+    table.append_row(Row::flexible_height(vec![
+        Cell::right("fig"),
+        Cell::new(""),
+        Cell::new("n/a"),
+        Cell::new("eleven"),
+    ]));
+    let expected = concat!(
+        "name   price  ok  mixed \n",
+        "apple  1,200 yes  1     \n",
+        "kiwi    -3.5  no  one   \n",
+        "   fig       n/a  eleven\n",
+    );
+    assert_eq!(table.to_string(), expected);
+}
+
+#[test]
+fn test_numeric_text() {
+    // infinities and NaN are not numbers
+    let mut table = Table::new(vec![Column::fixed_width(5)]);
+    table.set_color_mode(ColorMode::Always);
+    for text in ["inf", "NaN", "-inf"] {
+        table.append_row(Row::flexible_height(vec![Cell::new(text)]));
+    }
+    assert_eq!(table.to_string(), "inf  \nNaN  \n-inf \n");
+
+    // thousands separators are understood by inference, sorting and rules alike
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    table.set_color_mode(ColorMode::Always);
+    for text in ["1,200", "950", "$3"] {
+        table.append_row(Row::flexible_height(vec![Cell::new(text)]));
+    }
+    table.sort_by_column(0, Order::Ascending);
+    table.add_rule(0, Condition::GreaterThan(1000.0), Style::new().bold());
+    let expected = [
+        "   $3".to_owned(),
+        "  950".to_owned(),
+        Style::new().bold().paint("1,200"),
+    ];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_sort() {
+    let mut table = Table::new(vec![Column::flexible_width(), Column::flexible_width()]);
+    table.set_header_rows(2);
+    table.set_footer_rows(1);
+    table.append_row(Row::flexible_height(vec![Cell::left("f"), Cell::left("n")]));
+    table.append_row(Border::Single.into());
+    for (file, n) in [
+        ("file10", "2"),
+        ("file2", "x"),
+        ("file1", "10"),
+        ("File3", "2"),
+    ] {
+        table.append_row(Row::flexible_height(vec![Cell::left(file), Cell::left(n)]));
+        if file == "file2" {
+            table.append_row(Border::Single.into());
+        }
+    }
+    table.append_row(Row::flexible_height(vec![Cell::left("z"), Cell::left("0")]));
+    let column = |table: &Table, k: usize| {
+        table
+            .lines()
+            .map(|line| line.chars().skip(k * 6).take(6).collect::<String>())
+            .map(|s| s.trim_end().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    table.sort_by_column(0, Order::Ascending);
+    let expected = [
+        "f",
+        "──────",
+        "File3",
+        "file1",
+        "──────",
+        "file10",
+        "file2",
+        "z",
+    ];
+    assert_eq!(column(&table, 0), expected);
+
+    table.sort_by_column_natural(0, Order::Descending);
+    let expected = [
+        "f",
+        "──────",
+        "file10",
+        "file2",
+        "──────",
+        "file1",
+        "File3",
+        "z",
+    ];
+    assert_eq!(column(&table, 0), expected);
+
+    // numbers come first, equal rows keep their order
+    table.sort_by_column(1, Order::Ascending);
+    let expected = [
+        "f",
+        "──────",
+        "file10",
+        "File3",
+        "──────",
+        "file1",
+        "file2",
+        "z",
+    ];
+    assert_eq!(column(&table, 0), expected);
+
+    table.sort_by(|a, b| b.len().cmp(&a.len()));
+    assert_eq!(column(&table, 0), expected);
+
+    // in descending order too, numbers come before text and lacking cells come last
+    let mut table = Table::new(vec![Column::flexible_width()]);
+    for cell in [Some("3"), None, Some("n/a"), Some("10"), Some("2")] {
+        table.append_row(Row::flexible_height(
+            cell.into_iter().map(Cell::left).collect(),
+        ));
+    }
+    table.sort_by_column(0, Order::Descending);
+    let expected = ["10 ", "3  ", "2  ", "n/a", "   "];
+    assert_eq!(table.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_projection() {
+    let mut table = Table::new(vec![
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.set_header_rows(3);
+    table.set_footer_rows(1);
+    table.add_rule(2, Condition::Contains("!".to_owned()), Style::new().bold());
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("a"),
+        Cell::left("b"),
+        Cell::left("c"),
+    ]));
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("1"),
+        Cell::left("x"),
+        Cell::left("!"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("2")]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("3"),
+        Cell::left("y"),
+        Cell::left("z"),
+    ]));
+    table.append_row(Border::Double.into());
+
+    let view = table
+        .filter_rows(|cells| cells.len() == 3)
+        .select_columns(&[2, 0]);
+    let bold = |s: &str| Style::new().bold().paint(s);
+    let expected = [
+        "╔═╤═╗".to_owned(),
+        "║c│a║".to_owned(),
+        "╠═╪═╣".to_owned(),
+        format!("║{}│1║", bold("!")),
+        "╟─┼─╢".to_owned(),
+        "║z│3║".to_owned(),
+        "╚═╧═╝".to_owned(),
+    ];
+    assert_eq!(view.lines().collect::<Vec<_>>(), expected);
+
+    // lacking cells are kept lacking
+    let view = table
+        .filter_rows(|cells| cells.len() < 3)
+        .select_columns(&[1]);
+    let expected = ["╔═╗", "║b║", "╠═╣", "║ ║", "╚═╝"];
+    assert_eq!(view.lines().collect::<Vec<_>>(), expected);
+
+    // columns past the last are ignored
+    assert_eq!(
+        table.select_columns(&[2, 3, 0]),
+        table.select_columns(&[2, 0])
+    );
+    let mut sorted = table.clone();
+    sorted.sort_by_column(3, Order::Descending);
+    assert_eq!(sorted, table);
+}
+
+#[test]
+fn test_paginate() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(1),
+        Border::Single.into(),
+    ]);
+    table.set_header_rows(3);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("h")]));
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("a")]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("b")]));
+    table.append_row(Row::fixed_height(2, vec![Cell::left("c")]));
+    table.append_row(Row::fixed_height(3, vec![Cell::left("d")]));
+    table.append_row(Border::Single.into());
+
+    let pages = table.paginate(6);
+    let expected = [
+        vec!["┌─┐", "│h│", "╞═╡", "│a│", "└─┘"],
+        vec!["┌─┐", "│h│", "╞═╡", "│b│", "└─┘"],
+        vec!["┌─┐", "│h│", "╞═╡", "│c│", "│ │", "└─┘"],
+        vec!["┌─┐", "│h│", "╞═╡", "│d│", "│ │", "└─┘"],
+        vec!["┌─┐", "│h│", "╞═╡", "│ │", "└─┘"],
+    ];
+    assert_eq!(pages, expected);
+
+    // the whole table fits on one page
+    assert_eq!(table.paginate(100), [table.lines().collect::<Vec<_>>()]);
+
+    // the separator below the header is repeated even if the header does not count it
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(1),
+        Border::Single.into(),
+    ]);
+    table.set_header_rows(2);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::left("h")]));
+    table.append_row(Border::Double.into());
+    for text in ["a", "b", "c"] {
+        table.append_row(Row::flexible_height(vec![Cell::left(text)]));
+    }
+    table.append_row(Border::Single.into());
+
+    let expected = [
+        vec!["┌─┐", "│h│", "╞═╡", "│a│", "│b│", "└─┘"],
+        vec!["┌─┐", "│h│", "╞═╡", "│c│", "└─┘"],
+    ];
+    assert_eq!(table.paginate(6), expected);
+
+    // pages too short for a line of the body still hold one
+    let pages = table.paginate(0);
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[2], ["┌─┐", "│h│", "╞═╡", "│c│", "└─┘"]);
+}
+
+#[test]
+fn test_split_columns() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("id"),
+        Cell::left("aaaa"),
+        Cell::left("bb"),
+        Cell::left("cccccc"),
+    ]));
+
+    let bands: Vec<Vec<String>> = table
+        .split_columns(12, &[0])
+        .iter()
+        .map(|band| band.lines().collect())
+        .collect();
+    let expected = [vec!["│id║aaaa│bb│"], vec!["│id║cccccc│"]];
+    assert_eq!(bands, expected);
+
+    // bands hold a column even if it does not fit
+    let bands = table.split_columns(4, &[]);
+    assert_eq!(bands.len(), 4);
+    assert_eq!(bands[3].lines().collect::<Vec<_>>(), ["│cccccc│"]);
+
+    // sticky columns past the last are ignored
+    assert_eq!(
+        table.split_columns(12, &[0, 9]),
+        table.split_columns(12, &[0])
+    );
+}
+
+#[test]
+fn test_transpose() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::fixed_width(5).with_align(Align::Right),
+        Border::Single.into(),
+    ]);
+    table.set_color_mode(ColorMode::Always);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("name"),
+        Cell::left("age"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left_with_style("bob", Style::new().bold()),
+        Cell::new("42"),
+    ]));
+    table.append_row(Row::flexible_height(vec![Cell::new("eve")]));
+    table.append_row(Border::Single.into());
+
+    let transposed = table.transpose();
+    let bold = Style::new().bold();
+    let expected = [
+        "┌────┬──────┐".to_owned(),
+        format!("│name│{}eve│", bold.paint("bob")),
+        "╞════╪══════╡".to_owned(),
+        "│age │ 42   │".to_owned(),
+        "└────┴──────┘".to_owned(),
+    ];
+    assert_eq!(transposed.lines().collect::<Vec<_>>(), expected);
+    assert_eq!(transposed.transpose().transpose(), transposed);
+}
+
+#[test]
+fn test_records() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.set_header_rows(3);
+    table.set_format(1, Format::new().thousands(','));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::center("name"),
+        Cell::center("salary"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("alice"),
+        Cell::value(120000),
+    ]));
+    table.append_row(Row::flexible_height(vec![Cell::new("bob")]));
+    table.append_row(Border::Single.into());
+
+    let expected = concat!(
+        "┌─[ RECORD 1 ]─┐\n",
+        "│name  ║alice  │\n",
+        "│salary║120,000│\n",
+        "├─[ RECORD 2 ]─┤\n",
+        "│name  ║bob    │\n",
+        "│salary║       │\n",
+        "└──────╨───────┘\n",
+    );
+    assert_eq!(table.records().to_string(), expected);
+}
+
+#[test]
+fn test_records_short_columns() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.set_header_rows(3);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("name"),
+        Cell::new("n"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::new("alice"), Cell::new(1)]));
+    table.append_row(Border::Single.into());
+
+    let expected = concat!(
+        "┌─[ RECORD 1 ]─┐\n",
+        "│name│alice    │\n",
+        "│n   │1        │\n",
+        "└────┴─────────┘\n",
+    );
+    assert_eq!(table.records().to_string(), expected);
+
+    // a label longer than the line stops before the closing frame
+    table.set_border_label(0, "[ USERS ]");
+    assert_eq!(table.lines().next().unwrap(), "┌─[ USER┐");
+}
+
+#[test]
+fn test_parse() {
+    let mut table = Table::new(vec![
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::fixed_width(7),
+        Border::Double.into(),
+    ]);
+    table.set_border_style(Style::new().dimmed());
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::center("id"),
+        Cell::left("text"),
+    ]));
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::right("1"),
+        Cell::left("one two three"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::right("10"),
+        Cell::left_with_style("漢字", Style::new().bold()),
+    ]));
+    table.append_row(Border::Double.into());
+
+    let rendered = table.to_string();
+    let parsed = Table::parse_wrapped(&rendered).unwrap();
+    assert_eq!(parsed.dimensions(), table.dimensions());
+    assert_eq!(
+        parsed.to_string(),
+        ansi::strip(&rendered),
+        "the parsed table renders the same text"
+    );
+
+    let mut expected = Table::new(vec![
+        Border::Double.into(),
+        Column::fixed_width(2),
+        Border::Single.into(),
+        Column::fixed_width(7),
+        Border::Double.into(),
+    ]);
+    expected.append_row(Border::Double.into());
+    expected.append_row(Row::flexible_height(vec![
+        Cell::new("id"),
+        Cell::left("text"),
+    ]));
+    expected.append_row(Border::Double.into());
+    expected.append_row(Row::flexible_height(vec![
+        Cell::right("1"),
+        Cell::left("one two three"),
+    ]));
+    expected.append_row(Border::Single.into());
+    expected.append_row(Row::flexible_height(vec![
+        Cell::new("10"),
+        Cell::left("漢字"),
+    ]));
+    expected.append_row(Border::Double.into());
+    assert_eq!(parsed, expected);
+
+    assert_eq!(Table::parse(""), Err(ParseError::Empty));
+    assert_eq!(
+        Table::parse("┌─┐\n│a│\n└┘"),
+        Err(ParseError::UnevenLine { line: 2 })
+    );
+}
+
+#[test]
+fn test_parse_unseparated_rows() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("name"),
+        Cell::left("age"),
+    ]));
+    table.append_row(Border::Double.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("alice"),
+        Cell::right("30"),
+    ]));
+    table.append_row(Row::flexible_height(vec![
+        Cell::left("bob"),
+        Cell::right("4"),
+    ]));
+    table.append_row(Border::Single.into());
+
+    let rendered = table.to_string();
+    let parsed = Table::parse(&rendered).unwrap();
+    assert_eq!(parsed.to_string(), rendered);
+
+    let cols = || {
+        vec![
+            Border::Single.into(),
+            Column::fixed_width(5),
+            Border::Single.into(),
+            Column::fixed_width(3),
+            Border::Single.into(),
+        ]
+    };
+    let mut expected = Table::new(cols());
+    expected.append_row(Border::Single.into());
+    expected.append_row(Row::flexible_height(vec![
+        Cell::left("name"),
+        Cell::new("age"),
+    ]));
+    expected.append_row(Border::Double.into());
+    expected.append_row(Row::flexible_height(vec![
+        Cell::new("alice"),
+        Cell::right("30"),
+    ]));
+    expected.append_row(Row::flexible_height(vec![
+        Cell::left("bob"),
+        Cell::right("4"),
+    ]));
+    expected.append_row(Border::Single.into());
+    assert_eq!(parsed, expected);
+
+    // joining the lines merges the rows between borders
+    let mut joined = Table::new(cols());
+    joined.append_row(Border::Single.into());
+    joined.append_row(Row::flexible_height(vec![
+        Cell::left("name"),
+        Cell::new("age"),
+    ]));
+    joined.append_row(Border::Double.into());
+    joined.append_row(Row::flexible_height(vec![
+        Cell::left("alice bob"),
+        Cell::right("30 4"),
+    ]));
+    joined.append_row(Border::Single.into());
+    assert_eq!(Table::parse_wrapped(&rendered).unwrap(), joined);
+}
+
+#[test]
+fn test_parse_labelled_borders() {
+    let mut table = Table::new(vec![
+        Border::Single.into(),
+        Column::flexible_width(),
+        Border::Double.into(),
+        Column::flexible_width(),
+        Border::Single.into(),
+    ]);
+    table.set_header_rows(3);
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![
+        Cell::new("name"),
+        Cell::new("n"),
+    ]));
+    table.append_row(Border::Single.into());
+    table.append_row(Row::flexible_height(vec![Cell::new("alice"), Cell::new(1)]));
+    table.append_row(Row::flexible_height(vec![Cell::new("bob"), Cell::new(2)]));
+    table.append_row(Border::Single.into());
+
+    let rendered = table.records().to_string();
+    let parsed = Table::parse(&rendered).unwrap();
+    assert_eq!(parsed.to_string(), rendered);
+    assert_eq!(parsed.dimensions(), table.records().dimensions());
+
+    let mut expected = Table::new(vec![
+        Border::Single.into(),
+        Column::fixed_width(4),
+        Border::Double.into(),
+        Column::fixed_width(9),
+        Border::Single.into(),
+    ]);
+    for (i, (name, n)) in [("alice", "1"), ("bob", "2")].iter().enumerate() {
+        expected.set_border_label(3 * i, format!("[ RECORD {} ]", i + 1));
+        expected.append_row(Border::Single.into());
+        expected.append_row(Row::flexible_height(vec![
+            Cell::new("name"),
+            Cell::left(*name),
+        ]));
+        expected.append_row(Row::flexible_height(vec![Cell::left("n"), Cell::left(*n)]));
+    }
+    expected.append_row(Border::Single.into());
+    assert_eq!(parsed, expected);
+
+    assert_eq!(
+        Table::parse("┌───┐\n│abc│\n├a─b┤"),
+        Err(ParseError::BrokenBorder { line: 2 })
+    );
+}
+
+#[test]
+fn test_decimal_align_fixed_width() {
+    // the padding lining up separators is cut down to the room left in the column
+    let table = |width| {
+        let mut table = Table::new(vec![
+            Border::Single.into(),
+            Column::fixed_width(width).with_align(Align::Decimal { separator: '.' }),
+            Border::Single.into(),
+        ]);
+        for value in ["1.234", "12", "3.5"] {
+            table.append_row(Row::flexible_height(vec![Cell::new(value)]));
+        }
+        table.to_string()
+    };
+    assert_eq!(table(6), "│ 1.234│\n│12    │\n│ 3.5  │\n");
+    assert_eq!(table(4), "│1.23│\n│   4│\n│12  │\n│3.5 │\n");
+}
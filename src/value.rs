@@ -0,0 +1,292 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A typed value of a cell, displayed according to the [`Format`] of its column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+    Duration(Duration),
+    /// A size in bytes.
+    Bytes(u64),
+    Timestamp(SystemTime),
+}
+
+impl Value {
+    /// Returns the value as a number: durations in seconds, and timestamps in seconds since
+    /// the Unix epoch.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Value::Integer(n) => n as f64,
+            Value::Float(x) => x,
+            Value::Duration(d) => d.as_secs_f64(),
+            Value::Bytes(n) => n as f64,
+            Value::Timestamp(t) => match t.duration_since(UNIX_EPOCH) {
+                Ok(d) => d.as_secs_f64(),
+                Err(e) => -e.duration().as_secs_f64(),
+            },
+        }
+    }
+}
+
+/// Parses `text` as a finite number, possibly with thousands separators, a sign, a percent sign
+/// or a currency symbol.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    let text = text
+        .trim()
+        .trim_start_matches(['$', '€', '£', '¥'])
+        .trim_end_matches('%');
+    let number: f64 = text.replace([',', '_'], "").parse().ok()?;
+    Some(number).filter(|x| x.is_finite())
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Integer(n)
+    }
+}
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Integer(n.into())
+    }
+}
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Integer(n.into())
+    }
+}
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+impl From<f32> for Value {
+    fn from(x: f32) -> Self {
+        Value::Float(x.into())
+    }
+}
+impl From<Duration> for Value {
+    fn from(d: Duration) -> Self {
+        Value::Duration(d)
+    }
+}
+impl From<SystemTime> for Value {
+    fn from(t: SystemTime) -> Self {
+        Value::Timestamp(t)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Sizes {
+    #[default]
+    Exact,
+    Binary,
+    Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Time {
+    #[default]
+    Absolute,
+    RelativeToNow,
+    RelativeTo(SystemTime),
+}
+
+/// How the typed values of a column are displayed.
+///
+/// By default, numbers are written as with `Display`, durations with their largest units
+/// (`1h 2m 3s`), byte sizes in bytes and timestamps as UTC dates (`2021-01-31 12:00:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Format {
+    thousands_separator: Option<char>,
+    precision: Option<usize>,
+    sizes: Sizes,
+    time: Time,
+}
+
+impl Format {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Separates the thousands of integers, floats and exact byte sizes with `separator`.
+    pub fn thousands(self, separator: char) -> Self {
+        Format {
+            thousands_separator: Some(separator),
+            ..self
+        }
+    }
+
+    /// Sets the number of digits after the decimal point of floats, humanized byte sizes and
+    /// durations under a minute.
+    pub fn precision(self, digits: usize) -> Self {
+        Format {
+            precision: Some(digits),
+            ..self
+        }
+    }
+
+    /// Writes byte sizes with binary units, such as `1.2 GiB`.
+    pub fn binary_sizes(self) -> Self {
+        Format {
+            sizes: Sizes::Binary,
+            ..self
+        }
+    }
+
+    /// Writes byte sizes with decimal units, such as `1.2 GB`.
+    pub fn decimal_sizes(self) -> Self {
+        Format {
+            sizes: Sizes::Decimal,
+            ..self
+        }
+    }
+
+    /// Writes timestamps relative to the time of rendering, such as `3 minutes ago`.
+    pub fn relative_time(self) -> Self {
+        Format {
+            time: Time::RelativeToNow,
+            ..self
+        }
+    }
+
+    /// Writes timestamps relative to `now`.
+    pub fn relative_to(self, now: SystemTime) -> Self {
+        Format {
+            time: Time::RelativeTo(now),
+            ..self
+        }
+    }
+
+    /// Formats `value` into the text of a cell.
+    pub fn format(&self, value: &Value) -> String {
+        match *value {
+            Value::Integer(n) => self.group(&n.to_string()),
+            Value::Float(x) if !x.is_finite() => x.to_string(),
+            Value::Float(x) => {
+                let s = match self.precision {
+                    Some(p) => format!("{:.*}", p, x),
+                    None => x.to_string(),
+                };
+                match s.split_once('.') {
+                    Some((int, frac)) => format!("{}.{}", self.group(int), frac),
+                    None => self.group(&s),
+                }
+            }
+            Value::Bytes(n) => self.format_size(n),
+            Value::Duration(d) => self.format_duration(d),
+            Value::Timestamp(t) => match self.time {
+                Time::Absolute => format_date(t),
+                Time::RelativeToNow => format_relative(t, SystemTime::now()),
+                Time::RelativeTo(now) => format_relative(t, now),
+            },
+        }
+    }
+
+    /// Inserts the thousands separator in the integer `digits`, which may have a sign.
+    fn group(&self, digits: &str) -> String {
+        let sep = match self.thousands_separator {
+            Some(sep) => sep,
+            None => return digits.to_owned(),
+        };
+        let (sign, digits) = digits.split_at(digits.starts_with('-') as usize);
+        let mut buf = sign.to_owned();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                buf.push(sep);
+            }
+            buf.push(c);
+        }
+        buf
+    }
+
+    fn format_size(&self, n: u64) -> String {
+        let (base, units) = match self.sizes {
+            Sizes::Exact => return format!("{} B", self.group(&n.to_string())),
+            Sizes::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+            Sizes::Decimal => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+        };
+        let mut size = n as f64;
+        let mut unit = 0;
+        while size >= base && unit + 1 < units.len() {
+            size /= base;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{} B", n)
+        } else {
+            format!("{:.*} {}", self.precision.unwrap_or(1), size, units[unit])
+        }
+    }
+
+    fn format_duration(&self, d: Duration) -> String {
+        if d.as_secs() < 60 {
+            return match self.precision {
+                None if d.as_secs() == 0 => format!("{}ms", d.as_millis()),
+                p => format!("{:.*}s", p.unwrap_or(0), d.as_secs_f64()),
+            };
+        }
+        let secs = d.as_secs();
+        let parts = [
+            (secs / 86400, "d"),
+            (secs / 3600 % 24, "h"),
+            (secs / 60 % 60, "m"),
+            (secs % 60, "s"),
+        ];
+        parts
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, unit)| format!("{}{}", n, unit))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Formats `t` as a UTC date and time.
+fn format_date(t: SystemTime) -> String {
+    let secs = Value::Timestamp(t).as_f64().floor() as i64;
+    let (days, time) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    // converts days since the epoch to a date of the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Formats `t` relative to `now`, in its largest whole unit.
+fn format_relative(t: SystemTime, now: SystemTime) -> String {
+    let (d, future) = match now.duration_since(t) {
+        Ok(d) => (d, false),
+        Err(e) => (e.duration(), true),
+    };
+    let secs = d.as_secs();
+    let (n, unit) = match secs {
+        0 => return "now".to_owned(),
+        1..=59 => (secs, "second"),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        86400..=31_535_999 => (secs / 86400, "day"),
+        _ => (secs / 31_536_000, "year"),
+    };
+    let plural = if n == 1 { "" } else { "s" };
+    if future {
+        format!("in {} {}{}", n, unit, plural)
+    } else {
+        format!("{} {}{} ago", n, unit, plural)
+    }
+}